@@ -1,2 +1,7 @@
+pub mod catalog_signature;
+pub mod catalog_update;
 pub mod catalogs_c;
+pub mod manifest;
 pub mod open_indiana_oi_userland_git;
+pub mod p5p_archive;
+pub mod pkg5_repository;
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use serde_json::Value;
+use sha1_smol::Sha1;
+
+use crate::assets::catalogs_c::open_json_file;
+
+/// for verifying signatures of catalog (detects truncated or corrupted downloads)
+///
+/// `_SIGNATURE` of every catalog part (and of `catalog.attrs`) contains SHA-1 of the part
+/// serialized as compact JSON with sorted keys, non-ASCII characters escaped and without
+/// `_SIGNATURE`. If `catalog_path` is
+/// catalog directory, signatures of parts are also compared with `signature-sha-1` of parts in
+/// `catalog.attrs`.
+pub fn verify_catalog_signatures(catalog_path: &Path) -> Result<(), String> {
+    if !catalog_path.is_dir() {
+        return verify_part(catalog_path).map(|_| ());
+    }
+
+    let attrs_path = catalog_path.join("catalog.attrs");
+    verify_part(&attrs_path)?;
+
+    let attrs = open_json_file(&attrs_path.to_path_buf())?;
+    let parts = attrs
+        .get("parts")
+        .and_then(|p| p.as_object())
+        .ok_or(format!("{}: missing parts", attrs_path.display()))?;
+
+    for (part, part_attrs) in parts {
+        let part_path = catalog_path.join(part);
+        if !part_path.is_file() {
+            return Err(format!(
+                "{}: part is listed in catalog.attrs, but it is missing",
+                part_path.display()
+            ));
+        }
+
+        let computed = verify_part(&part_path)?;
+
+        if let Some(expected) = part_attrs.get("signature-sha-1").and_then(|s| s.as_str()) {
+            if expected != computed {
+                return Err(format!(
+                    "{}: signature does not match catalog.attrs (expected {}, found {}), part is \
+                    from other version of catalog",
+                    part_path.display(),
+                    expected,
+                    computed
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies `_SIGNATURE` of catalog part and returns computed SHA-1
+fn verify_part(part_path: &Path) -> Result<String, String> {
+    let mut part = open_json_file(&part_path.to_path_buf())?;
+
+    let signature = part
+        .as_object_mut()
+        .ok_or(format!("{}: expect object", part_path.display()))?
+        .remove("_SIGNATURE")
+        .ok_or(format!("{}: missing _SIGNATURE", part_path.display()))?;
+
+    let expected = signature
+        .get("sha-1")
+        .and_then(|s| s.as_str())
+        .ok_or(format!(
+            "{}: missing sha-1 in _SIGNATURE",
+            part_path.display()
+        ))?;
+
+    let computed = signature_of(&part)?;
+
+    if expected != computed {
+        return Err(format!(
+            "{}: SHA-1 signature mismatch (expected {}, computed {}), file is corrupted or truncated",
+            part_path.display(),
+            expected,
+            computed
+        ));
+    }
+
+    Ok(computed)
+}
+
+/// Returns SHA-1 (hex) of catalog part without `_SIGNATURE`
+pub(crate) fn signature_of(part: &Value) -> Result<String, String> {
+    Ok(Sha1::from(to_signed_json(part)?).digest().to_string())
+}
+
+/// Serializes part the way pkg5 does it before signing (Python `json.dumps` with sorted keys,
+/// `(",", ":")` separators and `ensure_ascii`), characters outside of ASCII (and DEL) are escaped
+/// as `\uXXXX`, characters outside of BMP as surrogate pairs
+fn to_signed_json(part: &Value) -> Result<String, String> {
+    let content =
+        serde_json::to_string(part).map_err(|e| format!("failed to serialize part: {}", e))?;
+
+    // JSON syntax is ASCII, so non-ASCII characters can be only in strings
+    let mut escaped = String::with_capacity(content.len());
+    for c in content.chars() {
+        if c.is_ascii() && c != '\x7f' {
+            escaped.push(c);
+            continue;
+        }
+
+        for unit in c.encode_utf16(&mut [0; 2]) {
+            escaped.push_str(&format!("\\u{:04x}", unit));
+        }
+    }
+
+    Ok(escaped)
+}
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use fmri::{Version, FMRI};
+use serde_json::{Map, Value};
+
+use crate::{
+    assets::catalogs_c::{add_catalog, open_json_file},
+    packages::components::Components,
+};
+
+/// parts whose actions are in entries of update
+const UPDATE_PARTS: [&str; 2] = ["catalog.dependency.C", "catalog.summary.C"];
+
+/// for applying incremental catalog update (`catalog.update.<timestamp>.C`) on loaded Components
+///
+/// `add` entries add version of package (with actions from `catalog.dependency.C` and
+/// `catalog.summary.C` parts), `remove` entries remove version of package and package without
+/// versions is removed. `remove` entries need data created with `--history` (otherwise only
+/// effective version is known). Reverse runtime dependencies are distributed again, problems are
+/// not checked.
+pub fn load_catalog_update(components: &mut Components, update_path: &Path) -> Result<(), String> {
+    let update = open_json_file(&update_path.to_path_buf())?;
+
+    // versions to add, in format of catalog part (publisher -> package name -> versions)
+    let mut added = Map::new();
+    let mut removed: Vec<(FMRI, String)> = Vec::new();
+
+    for (publisher, packages) in update.as_object().ok_or("expect object")? {
+        if publisher == "_SIGNATURE" {
+            continue;
+        }
+
+        for (package_name, entries) in packages.as_object().ok_or("expect object")? {
+            for entry in entries.as_array().ok_or("expect array")? {
+                let version = entry
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .ok_or(format!("missing version of {} in update", package_name))?;
+
+                let versions = added
+                    .entry(publisher)
+                    .or_insert(Value::Object(Map::new()))
+                    .as_object_mut()
+                    .ok_or("expect object")?
+                    .entry(package_name)
+                    .or_insert(Value::Array(Vec::new()))
+                    .as_array_mut()
+                    .ok_or("expect array")?;
+                versions.retain(|v| v.get("version").and_then(|v| v.as_str()) != Some(version));
+
+                match entry.get("op-type").and_then(|o| o.as_str()) {
+                    Some("add") => {
+                        let mut actions: Vec<Value> = Vec::new();
+                        for part in UPDATE_PARTS {
+                            if let Some(part_actions) =
+                                entry.get(part).and_then(|p| p.get("actions"))
+                            {
+                                actions.extend(
+                                    part_actions
+                                        .as_array()
+                                        .ok_or("expect array")?
+                                        .iter()
+                                        .cloned(),
+                                );
+                            }
+                        }
+
+                        let mut package_version = Map::new();
+                        package_version.insert("actions".to_owned(), Value::Array(actions));
+                        package_version.insert("version".to_owned(), Value::from(version));
+                        versions.push(Value::Object(package_version));
+                    }
+                    Some("remove") if !components.is_history_kept() => {
+                        return Err(format!(
+                            "update removes version of {}, but data was created without --history, re-run `run` with --history",
+                            package_name
+                        ))
+                    }
+                    Some("remove") => removed.push((
+                        FMRI::parse_raw(package_name).map_err(|e| {
+                            format!("failed to parse fmri ({}): {}", package_name, e)
+                        })?,
+                        version.to_owned(),
+                    )),
+                    Some(o) => return Err(format!("unknown op-type: {}", o)),
+                    None => return Err(format!("missing op-type of {} in update", package_name)),
+                }
+            }
+        }
+    }
+
+    // packages with removed versions only
+    for packages in added.values_mut() {
+        if let Some(packages) = packages.as_object_mut() {
+            packages.retain(|_, versions| versions.as_array().is_some_and(|v| !v.is_empty()));
+        }
+    }
+
+    for (fmri, version) in removed {
+        let version = Version::new(version)
+            .map_err(|e| format!("failed to parse version ({}): {}", fmri, e))?;
+        components
+            .remove_package_version(&fmri, &version)
+            .map_err(|e| format!("failed to remove version of package {}: {}", fmri, e))?;
+    }
+
+    add_catalog(components, Value::Object(added))
+}
@@ -1,23 +1,122 @@
-use std::{fs::File, io::Read, path::PathBuf};
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
-use fmri::{FMRI, FMRIList, Publisher, Version};
-use log::debug;
-use serde_json::Value;
+use fmri::{FMRIList, Publisher, Version, FMRI};
+use serde_json::{Map, Value};
 
 use crate::{
-    assets::catalogs_c::Name::{Obsolete, Other, Renamed},
+    assets::{
+        catalogs_c::Name::{Obsolete, Other, Renamed},
+        manifest::{parse_action_line, Action},
+    },
     packages::{
         components::Components,
         depend_types::DependTypes,
-        package::{Package, PackageVersion},
+        package::{Metadata, Package, PackageVersion},
     },
 };
 
+/// parts of catalog which are loaded by [`load_catalog`]
+const CATALOG_PARTS: [&str; 3] = [
+    "catalog.base.C",
+    "catalog.dependency.C",
+    "catalog.summary.C",
+];
+
 /// for loading catalog into Components
 pub fn load_catalog_c(components: &mut Components, source_path: &PathBuf) -> Result<(), String> {
     // open json file
     let json_value = open_json_file(source_path)?;
 
+    add_catalog(components, json_value)
+}
+
+/// for loading catalog directory (with `catalog.attrs`) into Components
+///
+/// Parts listed in `catalog.attrs` are merged by version of package, so packages get
+/// dependencies from `catalog.dependency.C` and metadata from `catalog.summary.C`.
+pub fn load_catalog(components: &mut Components, catalog_path: &Path) -> Result<(), String> {
+    let attrs = open_json_file(&catalog_path.join("catalog.attrs"))?;
+
+    match attrs.get("version").and_then(|v| v.as_u64()) {
+        Some(1) => {}
+        Some(v) => return Err(format!("unsupported catalog version: {}", v)),
+        None => return Err("missing version in catalog.attrs".to_owned()),
+    }
+
+    let parts = attrs
+        .get("parts")
+        .and_then(|p| p.as_object())
+        .ok_or("missing parts in catalog.attrs")?;
+
+    let mut catalog = Map::new();
+    for part in CATALOG_PARTS.iter().filter(|p| parts.contains_key(**p)) {
+        merge_catalog_part(&mut catalog, open_json_file(&catalog_path.join(part))?)
+            .map_err(|e| format!("failed to merge {}: {}", part, e))?;
+    }
+
+    add_catalog(components, Value::Object(catalog))
+}
+
+/// Merges catalog part into catalog, actions of the same version of package are joined
+fn merge_catalog_part(catalog: &mut Map<String, Value>, part: Value) -> Result<(), String> {
+    for (publisher, packages) in part.as_object().ok_or("expect object")? {
+        if publisher == "_SIGNATURE" {
+            continue;
+        }
+
+        let catalog_packages = catalog
+            .entry(publisher)
+            .or_insert(Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or("expect object")?;
+
+        for (package_name, package_versions) in packages.as_object().ok_or("expect object")? {
+            let catalog_versions = catalog_packages
+                .entry(package_name)
+                .or_insert(Value::Array(Vec::new()))
+                .as_array_mut()
+                .ok_or("expect array")?;
+
+            for package_version in package_versions.as_array().ok_or("expect array")? {
+                let package_version = package_version.as_object().ok_or("expect object")?;
+                let version = package_version.get("version").ok_or("missing version")?;
+
+                let catalog_version = match catalog_versions
+                    .iter_mut()
+                    .position(|v| v.get("version") == Some(version))
+                {
+                    Some(i) => &mut catalog_versions[i],
+                    None => {
+                        catalog_versions.push(Value::Object(Map::new()));
+                        catalog_versions.last_mut().unwrap()
+                    }
+                }
+                .as_object_mut()
+                .ok_or("expect object")?;
+
+                for (key, value) in package_version {
+                    match (key.as_str(), catalog_version.get_mut(key)) {
+                        ("actions", Some(Value::Array(actions))) => {
+                            actions.extend(value.as_array().ok_or("expect array")?.iter().cloned())
+                        }
+                        _ => {
+                            catalog_version.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// adds packages from catalog (publisher -> package name -> versions) into Components
+pub(crate) fn add_catalog(components: &mut Components, json_value: Value) -> Result<(), String> {
     // for every publisher(String) nad packages(Object) in json
     for (publisher, packages) in json_value.as_object().ok_or("expect object")? {
         // skip _SIGNATURE
@@ -47,6 +146,7 @@ pub fn load_catalog_c(components: &mut Components, source_path: &PathBuf) -> Res
                 let mut runtime_dependencies: Vec<DependTypes> = Vec::new();
                 let mut obsolete = false;
                 let mut renamed = false;
+                let mut metadata = Metadata::default();
 
                 // for key(String)[actions|version] and value(array|String) in package_version
                 for (key, value) in package_version.as_object().ok_or("expect object")? {
@@ -65,6 +165,9 @@ pub fn load_catalog_c(components: &mut Components, source_path: &PathBuf) -> Res
                                 }
                                 ParsedAction::Obsolete => obsolete = true,
                                 ParsedAction::Renamed => renamed = true,
+                                ParsedAction::Metadata(name, values) => {
+                                    metadata.set(&name, values);
+                                }
                                 ParsedAction::Other => {}
                             }
                         }
@@ -79,10 +182,13 @@ pub fn load_catalog_c(components: &mut Components, source_path: &PathBuf) -> Res
                         package_version.add_runtime_dependencies(&mut runtime_dependencies);
                         package_version.set_obsolete(obsolete);
                         package_version.set_renamed(renamed);
+                        package_version.set_metadata(metadata.clone());
 
                         package.add_package_version(package_version).map_err(|e| {
                             format!("failed to add package version into package: {}", e)
                         })?;
+                    } else if key.starts_with("signature-") {
+                        // signatures of manifest are in catalog.base.C
                     } else {
                         return Err(format!("unknown key: {} (expect version or actions)", key));
                     }
@@ -94,22 +200,29 @@ pub fn load_catalog_c(components: &mut Components, source_path: &PathBuf) -> Res
         }
     }
 
-    components.remove_old_versions();
+    if components.is_history_kept() {
+        components.sort_versions();
+    } else {
+        components.remove_old_versions();
+    }
     components.distribute_reverse_runtime_dependencies();
 
     Ok(())
 }
 
-enum ParsedAction {
+pub(crate) enum ParsedAction {
     Dependency(Box<DependTypes>),
     Obsolete,
     Renamed,
+    /// name and values of set action stored in [`Metadata`]
+    Metadata(String, Vec<String>),
     Other,
 }
 
 enum Name {
     Obsolete,
     Renamed,
+    Metadata(String, Vec<String>),
     Other,
 }
 
@@ -128,6 +241,7 @@ fn parse_action(action: String) -> Result<ParsedAction, String> {
             {
                 Obsolete => ParsedAction::Obsolete,
                 Renamed => ParsedAction::Renamed,
+                Name::Metadata(name, values) => ParsedAction::Metadata(name, values),
                 Other => ParsedAction::Other,
             },
         );
@@ -136,130 +250,34 @@ fn parse_action(action: String) -> Result<ParsedAction, String> {
     Err(format!("other unknown action: {}", &action.clone()))
 }
 
-enum Attribute {
-    Fmri(FMRI),
-    DType(String),
-    Name(String),
-    Value(String),
-    Predicate(FMRI),
-    Other,
-}
-
-struct Attributes(Vec<Attribute>);
-
-impl Attributes {
-    /// returns only fmri and type attribute
-    fn parse_attributes(attributes_string: String) -> Result<Self, String> {
-        let mut attributes = Self(vec![]);
-
-        for attribute_value in attributes_string.split_whitespace() {
-            if !attribute_value.contains('=') {
-                return Err("expected attribute, but it isn't! (attribute has \"=\")".to_owned());
-            }
-            let (attribute, value) = attribute_value
-                .split_once('=')
-                .ok_or("bad attribute value".to_string())?;
-            let att = match attribute {
-                "fmri" => Attribute::Fmri(
-                    FMRI::parse_raw(value).map_err(|e| format!("failed to parse fmri: {}", e))?,
-                ),
-                "type" => Attribute::DType(value.to_owned()),
-                "name" => Attribute::Name(value.to_owned()),
-                "value" => Attribute::Value(value.to_owned()),
-                "predicate" => Attribute::Predicate(
-                    FMRI::parse_raw(value).map_err(|e| format!("failed to parse fmri: {}", e))?,
-                ),
-                _ => {
-                    debug!("Unknown attribute found: {} value: {}", attribute, value);
-                    Attribute::Other
-                }
-            };
-
-            attributes.0.push(att)
-        }
-
-        Ok(attributes)
-    }
-
-    fn get_type_from_attributes(&self) -> Option<&String> {
-        for attribute in &self.0 {
-            if let Attribute::DType(d_type) = attribute {
-                return Some(d_type);
-            }
-        }
-        None
-    }
-
-    fn get_name_from_attributes(&self) -> Option<&String> {
-        for attribute in &self.0 {
-            if let Attribute::Name(name) = attribute {
-                return Some(name);
-            }
-        }
-        None
-    }
-
-    fn get_value_from_attributes(&self) -> Option<&String> {
-        for attribute in &self.0 {
-            if let Attribute::Value(value) = attribute {
-                return Some(value);
-            }
-        }
-        None
-    }
-
-    fn get_fmri_from_attributes(&self) -> Option<FMRI> {
-        for attribute in &self.0 {
-            if let Attribute::Fmri(fmri) = attribute {
-                return Some(fmri.clone());
-            }
-        }
-        None
-    }
-
-    fn get_predicate_from_attributes(&self) -> Option<FMRI> {
-        for attribute in &self.0 {
-            if let Attribute::Predicate(fmri) = attribute {
-                return Some(fmri.clone());
-            }
-        }
-        None
-    }
-}
-
-/// parses set action
+/// parses set action, values can be quoted (e.g. `set name=pkg.summary value="foo bar"`)
 fn parse_set(set: String) -> Result<Name, String> {
     if !set.starts_with("set") {
         return Err("trying to parse set action, but it is not set".to_owned());
     }
 
-    let attributes = Attributes::parse_attributes(set.trim_start_matches("set").to_owned())
-        .map_err(|e| format!("failed to parse attributes: {}", e))?;
-
-    if attributes
-        .get_name_from_attributes()
-        .ok_or("failed to get name attribute")?
-        == "pkg.obsolete"
-        && attributes
-            .get_value_from_attributes()
-            .ok_or("failed to get value attribute")?
-            == "true"
-    {
+    let action =
+        parse_action_line(&set).map_err(|e| format!("failed to parse attributes: {}", e))?;
+
+    let name = action.get("name").ok_or("failed to get name attribute")?;
+    let values = action.get_all("value");
+    let value = values.first().ok_or("failed to get value attribute")?;
+
+    if name == "pkg.obsolete" && *value == "true" {
         return Ok(Obsolete);
     }
 
-    if attributes
-        .get_name_from_attributes()
-        .ok_or("failed to get name attribute")?
-        == "pkg.renamed"
-        && attributes
-            .get_value_from_attributes()
-            .ok_or("failed to get value attribute")?
-            == "true"
-    {
+    if name == "pkg.renamed" && *value == "true" {
         return Ok(Renamed);
     }
 
+    if Metadata::NAMES.contains(&name.as_str()) {
+        return Ok(Name::Metadata(
+            name.clone(),
+            values.into_iter().cloned().collect(),
+        ));
+    }
+
     Ok(Other)
 }
 
@@ -270,35 +288,28 @@ fn parse_depend(depend: String) -> Result<DependTypes, String> {
         return Err("trying to parse depend action, but it is not depend".to_owned());
     }
 
-    let attributes = Attributes::parse_attributes(depend.trim_start_matches("depend").to_owned())
-        .map_err(|e| format!("failed to parse attributes: {}", e))?;
+    let action =
+        parse_action_line(&depend).map_err(|e| format!("failed to parse attributes: {}", e))?;
 
-    let d_type = attributes
-        .get_type_from_attributes()
-        .ok_or("failed to get type attribute")?;
+    depend_from_action(&action)
+}
 
-    return Ok(match d_type.as_str() {
-        "require" => DependTypes::Require(
-            attributes
-                .get_fmri_from_attributes()
-                .ok_or("failed to get fmri attribute")?,
-        ),
-        "optional" => DependTypes::Optional(
-            attributes
-                .get_fmri_from_attributes()
-                .ok_or("failed to get fmri attribute")?,
-        ),
-        "incorporate" => DependTypes::Incorporate(
-            attributes
-                .get_fmri_from_attributes()
-                .ok_or("failed to get fmri attribute")?,
-        ),
+/// Creates [`DependTypes`] from parsed depend action (values can be quoted)
+pub(crate) fn depend_from_action(action: &Action) -> Result<DependTypes, String> {
+    let parse =
+        |value: &String| FMRI::parse_raw(value).map_err(|e| format!("failed to parse fmri: {}", e));
+    let fmri = || parse(action.get("fmri").ok_or("failed to get fmri attribute")?);
+
+    let d_type = action.get("type").ok_or("failed to get type attribute")?;
+
+    Ok(match d_type.as_str() {
+        "require" => DependTypes::Require(fmri()?),
+        "optional" => DependTypes::Optional(fmri()?),
+        "incorporate" => DependTypes::Incorporate(fmri()?),
         "require-any" => {
             let mut fmri_list = FMRIList::new();
-            for attribute in attributes.0 {
-                if let Attribute::Fmri(fmri) = attribute {
-                    fmri_list.add(fmri)
-                }
+            for value in action.get_all("fmri") {
+                fmri_list.add(parse(value)?)
             }
             if fmri_list.is_empty() {
                 return Err("cant find fmri attribute in require-any depend".to_owned());
@@ -306,20 +317,16 @@ fn parse_depend(depend: String) -> Result<DependTypes, String> {
             DependTypes::RequireAny(fmri_list)
         }
         "conditional" => DependTypes::Conditional(
-            attributes
-                .get_fmri_from_attributes()
-                .ok_or("failed to get fmri attribute")?,
-            attributes
-                .get_predicate_from_attributes()
-                .ok_or("failed to get predicate attribute")?,
-        ),
-        "group" => DependTypes::Group(
-            attributes
-                .get_fmri_from_attributes()
-                .ok_or("failed to get fmri attribute")?,
+            fmri()?,
+            parse(
+                action
+                    .get("predicate")
+                    .ok_or("failed to get predicate attribute")?,
+            )?,
         ),
+        "group" => DependTypes::Group(fmri()?),
         _ => return Err(format!("unknown depend type: {}", d_type)),
-    });
+    })
 }
 
 pub fn open_json_file(source_path: &PathBuf) -> Result<Value, String> {
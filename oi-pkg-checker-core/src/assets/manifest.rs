@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+/// Action from package manifest, e.g. `depend fmri=pkg:/foo type=require`
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Action {
+    /// e.g. `depend`, `set`, `file`
+    pub name: String,
+    /// values without attribute name (hash of file payload)
+    pub payload: Vec<String>,
+    /// attributes in order, attribute can be there multiple times
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Action {
+    /// Returns the first value of attribute
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Returns all values of attribute
+    pub fn get_all(&self, key: &str) -> Vec<&String> {
+        self.attributes
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .collect()
+    }
+}
+
+/// Parses manifest, lines ending with `\` continue on the next line, comments are skipped
+pub fn parse_manifest(content: &str) -> Result<Vec<Action>, String> {
+    let mut actions: Vec<Action> = Vec::new();
+    let mut line = String::new();
+
+    for (number, part) in content.lines().enumerate() {
+        match part.strip_suffix('\\') {
+            Some(part) => {
+                line.push_str(part);
+                line.push(' ');
+                continue;
+            }
+            None => line.push_str(part),
+        }
+
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            actions.push(
+                parse_action_line(trimmed)
+                    .map_err(|e| format!("failed to parse line {}: {}", number + 1, e))?,
+            );
+        }
+        line.clear();
+    }
+
+    Ok(actions)
+}
+
+/// Parses one action, values can be quoted with `"` or `'`
+pub fn parse_action_line(line: &str) -> Result<Action, String> {
+    let mut chars = line.chars().peekable();
+    let mut tokens: Vec<(String, Option<String>)> = Vec::new();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            key.push(c);
+        }
+
+        if chars.next_if_eq(&'=').is_none() {
+            tokens.push((key, None));
+            continue;
+        }
+
+        let mut value = String::new();
+        match chars.next_if(|c| *c == '"' || *c == '\'') {
+            Some(quote) => loop {
+                match chars.next() {
+                    None => return Err(format!("unterminated quote in value of {}", key)),
+                    Some('\\') => value.push(chars.next().ok_or("unexpected end of line")?),
+                    Some(c) if c == quote => break,
+                    Some(c) => value.push(c),
+                }
+            },
+            None => {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+        tokens.push((key, Some(value)));
+    }
+
+    let mut tokens = tokens.into_iter();
+    let name = match tokens.next() {
+        Some((name, None)) => name,
+        _ => return Err(format!("missing action name: {}", line)),
+    };
+
+    let mut action = Action {
+        name,
+        payload: Vec::new(),
+        attributes: Vec::new(),
+    };
+    for (key, value) in tokens {
+        match value {
+            Some(value) => action.attributes.push((key, value)),
+            None => action.payload.push(key),
+        }
+    }
+
+    Ok(action)
+}
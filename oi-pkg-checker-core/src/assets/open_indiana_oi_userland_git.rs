@@ -1,4 +1,6 @@
 use std::{
+    cmp::Ordering,
+    fs,
     fs::File,
     io::Read,
     path::{Path, PathBuf},
@@ -6,11 +8,11 @@ use std::{
 };
 
 use fmri::FMRI;
-use log::warn;
+use log::{debug, warn};
 
 use crate::{
-    assets::catalogs_c::open_json_file,
-    get,
+    assets::{catalogs_c::open_json_file, manifest::parse_manifest},
+    get, get_mut,
     packages::dependency_type::{
         DependencyTypes,
         DependencyTypes::{Build, Runtime, SystemBuild, SystemTest, Test},
@@ -62,7 +64,9 @@ pub fn load_git(components: &mut Components, oi_userland_components: &Path) -> R
             .last()
             .ok_or(format!("failed to get component name from line: {}", line))?
             .to_owned();
-        let component_path = format!("{}/{}", components_path, component_name);
+        let component_path = component_path(oi_userland_components, &component_name)
+            .to_string_lossy()
+            .to_string();
 
         let mut packages: Vec<FMRI> = Vec::new();
         for fmri in open_json_file(&PathBuf::from(format!("{}/pkg5", component_path)))?
@@ -114,8 +118,9 @@ pub fn load_git(components: &mut Components, oi_userland_components: &Path) -> R
     for component in components.clone().get_components() {
         let component = get!(component);
 
-        let history_file = format!("{}/{}/history", components_path, component.get_name());
-        let history_file_path = Path::new(&history_file);
+        let history_file =
+            component_path(oi_userland_components, component.get_name()).join("history");
+        let history_file_path = history_file.as_path();
 
         if !history_file_path.exists() {
             continue;
@@ -159,7 +164,8 @@ pub fn load_git(components: &mut Components, oi_userland_components: &Path) -> R
                         .set_package_obsolete(fmri)
                         .map_err(|e| format!("failed to set package obsolete: {}", e))?;
                 }
-                (3, [raw_fmri, _, "noincorporate"]) | (2, [raw_fmri, _]) => {
+                (3, [raw_fmri, raw_renamed_to, "noincorporate"])
+                | (2, [raw_fmri, raw_renamed_to]) => {
                     // renamed package
 
                     let fmri = FMRI::parse_raw(raw_fmri)
@@ -169,8 +175,15 @@ pub fn load_git(components: &mut Components, oi_userland_components: &Path) -> R
                         todo!()
                     }
 
+                    let renamed_to = FMRI::parse_raw(raw_renamed_to)
+                        .map_err(|e| format!("failed to parse fmri: {}", e))?;
+
+                    components
+                        .check_history_rename(&fmri, &renamed_to, component.get_name())
+                        .map_err(|e| format!("failed to check rename: {}", e))?;
+
                     components
-                        .set_package_renamed(fmri)
+                        .set_package_renamed(fmri, Some(renamed_to))
                         .map_err(|e| format!("failed to set package renamed: {}", e))?;
                 }
                 (l, _) if l > 3 => {
@@ -198,23 +211,168 @@ pub fn load_git(components: &mut Components, oi_userland_components: &Path) -> R
     Ok(())
 }
 
+/// Loads all actions of packages from generated manifests of components
+/// (`build/manifest-<arch>-<package>.published`) into effective versions of these packages.
+///
+/// Components have to be built, components without manifests and packages which are not
+/// in catalog (or which are built in other version than is in catalog) are skipped.
+pub fn load_component_manifests(
+    components: &mut Components,
+    oi_userland_components: &Path,
+) -> Result<(), String> {
+    for component in components.clone().get_components() {
+        let build_path =
+            component_path(oi_userland_components, get!(component).get_name()).join("build");
+
+        let entries = match fs::read_dir(&build_path) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        let mut manifest_paths: Vec<PathBuf> = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("failed to read directory entry: {}", e))?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("manifest-") && name.ends_with(".published") {
+                manifest_paths.push(entry.path());
+            }
+        }
+        manifest_paths.sort();
+
+        for manifest_path in manifest_paths {
+            let content = fs::read_to_string(&manifest_path).map_err(|e| {
+                format!("failed to read manifest {}: {}", manifest_path.display(), e)
+            })?;
+            let actions = parse_manifest(&content).map_err(|e| {
+                format!(
+                    "failed to parse manifest {}: {}",
+                    manifest_path.display(),
+                    e
+                )
+            })?;
+
+            let fmri = actions
+                .iter()
+                .find(|a| a.name == "set" && a.get("name").is_some_and(|n| n == "pkg.fmri"))
+                .and_then(|a| a.get("value"))
+                .ok_or(format!("missing pkg.fmri in {}", manifest_path.display()))?;
+            let fmri = FMRI::parse_raw(fmri).map_err(|e| format!("failed to parse fmri: {}", e))?;
+
+            let package = match components.get_package_by_fmri(&fmri) {
+                Ok(package) => package,
+                Err(_) => {
+                    debug!(
+                        "package {} from {} is not in catalog",
+                        fmri,
+                        manifest_path.display()
+                    );
+                    continue;
+                }
+            };
+
+            let mut package = get_mut!(package);
+            let version = match package.get_effective_version_mut() {
+                Some(version) => version,
+                None => continue,
+            };
+
+            // built component can be newer or older than catalog
+            if fmri
+                .get_version_ref()
+                .as_ref()
+                .is_none_or(|v| v.cmp(&version.version) != Ordering::Equal)
+            {
+                debug!(
+                    "version of {} from {} is not version {} from catalog",
+                    fmri,
+                    manifest_path.display(),
+                    version.version
+                );
+                continue;
+            }
+
+            version.set_actions(actions);
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads COMPONENT_LICENSE and COMPONENT_LICENSE_FILE of components from their Makefiles
+pub fn load_component_licenses(
+    components: &mut Components,
+    oi_userland_components: &Path,
+) -> Result<(), String> {
+    for component in components.clone().get_components() {
+        let component_path = component_path(oi_userland_components, get!(component).get_name())
+            .to_string_lossy()
+            .to_string();
+
+        let [license, license_file] = print_values(
+            &component_path,
+            &mut components.problems,
+            ["COMPONENT_LICENSE", "COMPONENT_LICENSE_FILE"],
+        )
+        .map_err(|e| format!("failed to get license: {}", e))?
+        .map(|value| Some(value.trim().to_owned()).filter(|v| !v.is_empty()));
+        get_mut!(component).set_license(license, license_file);
+    }
+
+    Ok(())
+}
+
+/// Returns path of component in oi-userland/components
+pub fn component_path(oi_userland_components: &Path, component_name: &str) -> PathBuf {
+    oi_userland_components.join(component_name)
+}
+
 fn get_git_dependencies(
     component_path: &String,
     problems: &mut Problems,
     dependency_type: &DependencyTypes,
 ) -> Result<Vec<FMRI>, String> {
-    let mut make_command: String = "gmake ".to_owned();
+    let variable = match dependency_type {
+        Runtime => return Err("can not find runtime dependencies in git".to_string()),
+        Build => "REQUIRED_PACKAGES",
+        Test => "TEST_REQUIRED_PACKAGES",
+        SystemBuild => "USERLAND_REQUIRED_PACKAGES",
+        SystemTest => "USERLAND_TEST_REQUIRED_PACKAGES",
+    };
+
+    let mut fmri_list: Vec<FMRI> = Vec::new();
+    for raw_fmri in print_value(component_path, problems, variable)?.split_whitespace() {
+        fmri_list
+            .push(FMRI::parse_raw(raw_fmri).map_err(|e| format!("failed to parse fmri: {}", e))?);
+    }
+
+    Ok(fmri_list)
+}
+
+/// Returns value of variable from Makefile of component (`gmake print-value-<variable>`)
+fn print_value(
+    component_path: &String,
+    problems: &mut Problems,
+    variable: &str,
+) -> Result<String, String> {
+    let [value] = print_values(component_path, problems, [variable])?;
+    Ok(value)
+}
+
+/// Returns values of variables from Makefile of component with one make command, every
+/// `print-value-<variable>` target prints one line
+fn print_values<const N: usize>(
+    component_path: &String,
+    problems: &mut Problems,
+    variables: [&str; N],
+) -> Result<[String; N], String> {
+    let mut make_command: String = "gmake".to_owned();
 
     #[cfg(target_os = "linux")]
-    make_command.push_str("GSED=/usr/bin/sed ");
+    make_command.push_str(" GSED=/usr/bin/sed");
 
-    make_command.push_str(match dependency_type {
-        Runtime => return Err("can not find runtime dependencies in git".to_string()),
-        Build => "print-value-REQUIRED_PACKAGES",
-        Test => "print-value-TEST_REQUIRED_PACKAGES",
-        SystemBuild => "print-value-USERLAND_REQUIRED_PACKAGES",
-        SystemTest => "print-value-USERLAND_TEST_REQUIRED_PACKAGES",
-    });
+    for variable in variables {
+        make_command.push_str(&format!(" print-value-{}", variable));
+    }
 
     let command = Command::new("sh")
         .arg("-c")
@@ -229,11 +387,7 @@ fn get_git_dependencies(
         ));
     }
 
-    let mut fmri_list: Vec<FMRI> = Vec::new();
-    for raw_fmri in String::from_utf8_lossy(&command.stdout).split_whitespace() {
-        fmri_list
-            .push(FMRI::parse_raw(raw_fmri).map_err(|e| format!("failed to parse fmri: {}", e))?);
-    }
-
-    Ok(fmri_list)
+    let stdout = String::from_utf8_lossy(&command.stdout);
+    let mut lines = stdout.lines();
+    Ok(variables.map(|_| lines.next().unwrap_or_default().to_owned()))
 }
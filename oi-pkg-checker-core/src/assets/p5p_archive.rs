@@ -0,0 +1,170 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use crate::{
+    assets::pkg5_repository::{add_packages, decode, Manifest},
+    packages::components::Components,
+};
+
+/// size of tar header and data block
+const BLOCK: u64 = 512;
+
+/// for loading p5p archive (tar with pkg5 repository layout) into Components
+///
+/// Manifests are read from `publisher/<name>/pkg/<stem>/<version>` members, file payloads are
+/// skipped. Packages are added with [`Components::add_package`], so archive can be loaded over
+/// catalog and its versions of packages replace older versions from catalog.
+pub fn load_archive(components: &mut Components, archive_path: &Path) -> Result<(), String> {
+    let manifests = read_manifests(archive_path)?;
+
+    if manifests.is_empty() {
+        return Err(format!(
+            "no package manifests found in archive {}",
+            archive_path.display()
+        ));
+    }
+
+    for (publisher_name, manifests) in manifests {
+        add_packages(components, &publisher_name, manifests)?;
+    }
+
+    if components.is_history_kept() {
+        components.sort_versions();
+    } else {
+        components.remove_old_versions();
+    }
+    components.distribute_reverse_runtime_dependencies();
+
+    Ok(())
+}
+
+/// Returns manifests of every publisher in archive
+fn read_manifests(archive_path: &Path) -> Result<BTreeMap<String, Vec<Manifest>>, String> {
+    let mut archive = BufReader::new(
+        File::open(archive_path)
+            .map_err(|e| format!("failed to open archive {}: {}", archive_path.display(), e))?,
+    );
+
+    let mut manifests: BTreeMap<String, Vec<Manifest>> = BTreeMap::new();
+    // path from pax extended header or GNU long name of the next member
+    let mut long_name: Option<String> = None;
+
+    loop {
+        let mut header = [0u8; BLOCK as usize];
+        archive
+            .read_exact(&mut header)
+            .map_err(|e| format!("failed to read tar header: {}", e))?;
+
+        // end of archive
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+
+        let size = parse_size(&header[124..136])?;
+        let padding = (BLOCK - size % BLOCK) % BLOCK;
+        let name = long_name.take().unwrap_or_else(|| header_name(&header));
+
+        match header[156] {
+            b'x' => long_name = pax_path(&read_data(&mut archive, size, padding)?),
+            b'L' => {
+                let data = read_data(&mut archive, size, padding)?;
+                long_name = Some(field(&data))
+            }
+            b'0' | 0 => match manifest_path(&name)? {
+                Some((publisher, stem, version)) => {
+                    let content = String::from_utf8(read_data(&mut archive, size, padding)?)
+                        .map_err(|e| format!("manifest {} is not valid UTF-8: {}", name, e))?;
+                    manifests
+                        .entry(publisher)
+                        .or_default()
+                        .push((stem, version, content));
+                }
+                None => skip(&mut archive, size + padding)?,
+            },
+            _ => skip(&mut archive, size + padding)?,
+        }
+    }
+
+    Ok(manifests)
+}
+
+/// Returns (publisher, stem, version) if member is package manifest
+fn manifest_path(name: &str) -> Result<Option<(String, String, String)>, String> {
+    let parts = name
+        .trim_start_matches("./")
+        .split('/')
+        .collect::<Vec<&str>>();
+
+    match parts.as_slice() {
+        ["publisher", publisher, "pkg", stem, version] => Ok(Some((
+            publisher.to_string(),
+            decode(stem)?,
+            decode(version)?,
+        ))),
+        _ => Ok(None),
+    }
+}
+
+/// Returns member name from ustar header (prefix and name)
+fn header_name(header: &[u8]) -> String {
+    let name = field(&header[0..100]);
+    let prefix = match &header[257..262] == b"ustar" {
+        true => field(&header[345..500]),
+        false => String::new(),
+    };
+
+    match prefix.is_empty() {
+        true => name,
+        false => format!("{}/{}", prefix, name),
+    }
+}
+
+/// Returns `path` from pax extended header records (`<length> <key>=<value>\n`)
+fn pax_path(data: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(data).lines().find_map(|record| {
+        let (_, key_value) = record.split_once(' ')?;
+        key_value.strip_prefix("path=").map(|path| path.to_owned())
+    })
+}
+
+/// Parses member size, it is octal number or big-endian number if the high bit is set
+fn parse_size(field: &[u8]) -> Result<u64, String> {
+    if field[0] & 0x80 != 0 {
+        return Ok(field[1..]
+            .iter()
+            .fold(0, |size, byte| (size << 8) | *byte as u64));
+    }
+
+    let octal = String::from_utf8_lossy(field);
+    let octal = octal.trim_matches(|c: char| c == '\0' || c == ' ');
+    match octal.is_empty() {
+        true => Ok(0),
+        false => u64::from_str_radix(octal, 8)
+            .map_err(|e| format!("invalid size in tar header ({}): {}", octal, e)),
+    }
+}
+
+/// Returns NUL-terminated string from header field
+fn field(field: &[u8]) -> String {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+fn read_data(archive: &mut BufReader<File>, size: u64, padding: u64) -> Result<Vec<u8>, String> {
+    let mut data = vec![0u8; size as usize];
+    archive
+        .read_exact(&mut data)
+        .map_err(|e| format!("failed to read tar member: {}", e))?;
+    skip(archive, padding)?;
+    Ok(data)
+}
+
+fn skip(archive: &mut BufReader<File>, size: u64) -> Result<(), String> {
+    archive
+        .seek_relative(size as i64)
+        .map_err(|e| format!("failed to skip tar member: {}", e))
+}
@@ -0,0 +1,290 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use fmri::{Publisher, Version, FMRI};
+
+use crate::{
+    assets::{
+        catalogs_c::{depend_from_action, open_json_file, ParsedAction},
+        manifest::{parse_manifest, Action},
+    },
+    packages::{
+        components::Components,
+        package::{Metadata, Package, PackageVersion},
+    },
+};
+
+/// stem, version and content of package manifest
+pub(crate) type Manifest = (String, String, String);
+
+/// for loading local pkg5 file repository (e.g. `i386/repo` of oi-userland build) into Components
+///
+/// Packages of every publisher are listed in `publisher/<name>/catalog/catalog.base.C` (or found
+/// in `publisher/<name>/pkg` if there is no catalog) and their manifests are read from
+/// `publisher/<name>/pkg/<stem>/<version>`.
+pub fn load_repository(components: &mut Components, repository_path: &Path) -> Result<(), String> {
+    check_repository_version(repository_path)?;
+
+    for (publisher_name, publisher_path) in list_publishers(repository_path)? {
+        let mut manifests: Vec<Manifest> = Vec::new();
+
+        for (stem, version) in list_packages(&publisher_name, &publisher_path)? {
+            let manifest_path = publisher_path
+                .join("pkg")
+                .join(encode(&stem))
+                .join(encode(&version));
+
+            let content = fs::read_to_string(&manifest_path).map_err(|e| {
+                format!("failed to read manifest {}: {}", manifest_path.display(), e)
+            })?;
+
+            manifests.push((stem, version, content));
+        }
+
+        add_packages(components, &publisher_name, manifests)?;
+    }
+
+    if components.is_history_kept() {
+        components.sort_versions();
+    } else {
+        components.remove_old_versions();
+    }
+    components.distribute_reverse_runtime_dependencies();
+
+    Ok(())
+}
+
+/// Creates packages of publisher from manifests and adds them into Components
+pub(crate) fn add_packages(
+    components: &mut Components,
+    publisher_name: &str,
+    manifests: Vec<Manifest>,
+) -> Result<(), String> {
+    let publisher = Publisher::new(publisher_name.to_owned())
+        .map_err(|e| format!("failed to create publisher ({}): {}", publisher_name, e))?;
+
+    let mut packages: BTreeMap<String, Package> = BTreeMap::new();
+
+    for (stem, version, content) in manifests {
+        let package_version =
+            parse_package_version(&content, &version, components.are_actions_kept())
+                .map_err(|e| format!("failed to parse manifest of {}@{}: {}", stem, version, e))?;
+
+        let package = match packages.get_mut(&stem) {
+            Some(package) => package,
+            None => {
+                let mut fmri = FMRI::parse_raw(&stem)
+                    .map_err(|e| format!("failed to parse fmri ({}): {}", stem, e))?;
+                fmri.change_publisher(publisher.clone());
+                packages.entry(stem.clone()).or_insert(Package::new(fmri))
+            }
+        };
+
+        package
+            .add_package_version(package_version)
+            .map_err(|e| format!("failed to add package version into package: {}", e))?;
+    }
+
+    for package in packages.into_values() {
+        components.add_package(package);
+    }
+
+    Ok(())
+}
+
+/// Checks that `pkg5.repository` exists and repository has version 4
+fn check_repository_version(repository_path: &Path) -> Result<(), String> {
+    let config_path = repository_path.join("pkg5.repository");
+    let config = fs::read_to_string(&config_path).map_err(|e| {
+        format!(
+            "{} is not pkg5 repository (failed to read {}): {}",
+            repository_path.display(),
+            config_path.display(),
+            e
+        )
+    })?;
+
+    let version = config
+        .lines()
+        .filter_map(|l| l.split_once('='))
+        .find(|(key, _)| key.trim() == "version")
+        .map(|(_, value)| value.trim().to_owned());
+
+    match version.as_deref() {
+        Some("4") => Ok(()),
+        Some(v) => Err(format!("unsupported pkg5 repository version: {}", v)),
+        None => Err(format!("missing version in {}", config_path.display())),
+    }
+}
+
+/// Returns names and paths of publishers, sorted by name
+fn list_publishers(repository_path: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let publishers_path = repository_path.join("publisher");
+    let mut publishers: Vec<(String, PathBuf)> = Vec::new();
+
+    for entry in fs::read_dir(&publishers_path)
+        .map_err(|e| format!("failed to read {}: {}", publishers_path.display(), e))?
+    {
+        let entry = entry.map_err(|e| format!("failed to read directory entry: {}", e))?;
+        if entry.path().is_dir() {
+            publishers.push((
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            ));
+        }
+    }
+
+    publishers.sort();
+    Ok(publishers)
+}
+
+/// Returns (stem, version) of every package of publisher
+fn list_packages(
+    publisher_name: &str,
+    publisher_path: &Path,
+) -> Result<Vec<(String, String)>, String> {
+    let mut packages: Vec<(String, String)> = Vec::new();
+
+    let catalog_path = publisher_path.join("catalog").join("catalog.base.C");
+    if catalog_path.is_file() {
+        let catalog = open_json_file(&catalog_path)?;
+        let stems = match catalog.get(publisher_name) {
+            Some(stems) => stems.as_object().ok_or("expect object")?,
+            None => return Ok(packages),
+        };
+
+        for (stem, versions) in stems {
+            for version in versions.as_array().ok_or("expect array")? {
+                let version = version
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .ok_or(format!("missing version of {} in catalog", stem))?;
+                packages.push((stem.clone(), version.to_owned()));
+            }
+        }
+
+        return Ok(packages);
+    }
+
+    let pkg_path = publisher_path.join("pkg");
+    let read_dir = |path: &Path| -> Result<Vec<String>, String> {
+        let mut names: Vec<String> = Vec::new();
+        for entry in
+            fs::read_dir(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?
+        {
+            let entry = entry.map_err(|e| format!("failed to read directory entry: {}", e))?;
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+        names.sort();
+        Ok(names)
+    };
+
+    for stem in read_dir(&pkg_path)? {
+        for version in read_dir(&pkg_path.join(&stem))? {
+            packages.push((decode(&stem)?, decode(&version)?));
+        }
+    }
+
+    Ok(packages)
+}
+
+/// Creates package version from manifest, all actions are kept in it if `keep_actions` is set
+fn parse_package_version(
+    content: &str,
+    version: &str,
+    keep_actions: bool,
+) -> Result<PackageVersion, String> {
+    let mut package_version = PackageVersion::new(
+        Version::new(version.to_owned()).map_err(|e| format!("failed to parse version: {}", e))?,
+    );
+
+    let mut runtime_dependencies = Vec::new();
+    let mut obsolete = false;
+    let mut renamed = false;
+    let mut metadata = Metadata::default();
+
+    let actions = parse_manifest(content)?;
+
+    for action in &actions {
+        let is_flag = |action: &Action, name: &str| {
+            action.get("name").is_some_and(|n| n == name)
+                && action.get("value").is_some_and(|v| v == "true")
+        };
+
+        let parsed = match action.name.as_str() {
+            "depend" => ParsedAction::Dependency(Box::new(
+                depend_from_action(action)
+                    .map_err(|e| format!("failed to parse depend action: {}", e))?,
+            )),
+            "set" if is_flag(action, "pkg.obsolete") => ParsedAction::Obsolete,
+            "set" if is_flag(action, "pkg.renamed") => ParsedAction::Renamed,
+            "set" => match action.get("name") {
+                Some(name) if Metadata::NAMES.contains(&name.as_str()) => ParsedAction::Metadata(
+                    name.clone(),
+                    action.get_all("value").into_iter().cloned().collect(),
+                ),
+                _ => ParsedAction::Other,
+            },
+            _ => ParsedAction::Other,
+        };
+
+        match parsed {
+            ParsedAction::Dependency(d_type) => runtime_dependencies.push(*d_type),
+            ParsedAction::Obsolete => obsolete = true,
+            ParsedAction::Renamed => renamed = true,
+            ParsedAction::Metadata(name, values) => {
+                metadata.set(&name, values);
+            }
+            ParsedAction::Other => {}
+        }
+    }
+
+    package_version.add_runtime_dependencies(&mut runtime_dependencies);
+    package_version.set_obsolete(obsolete);
+    package_version.set_renamed(renamed);
+    package_version.set_metadata(metadata);
+    if keep_actions {
+        package_version.set_actions(actions);
+    }
+    Ok(package_version)
+}
+
+/// Percent-encodes name the way pkg5 does for file names (everything except `A-Za-z0-9_.-~`)
+pub(crate) fn encode(name: &str) -> String {
+    let mut encoded = String::new();
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'_' | b'.' | b'-' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Decodes percent-encoded file name
+pub(crate) fn decode(name: &str) -> Result<String, String> {
+    let bytes = name.as_bytes();
+    let mut decoded: Vec<u8> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = name
+                .get(i + 1..i + 3)
+                .ok_or(format!("invalid percent-encoding: {}", name))?;
+            decoded.push(
+                u8::from_str_radix(hex, 16)
+                    .map_err(|e| format!("invalid percent-encoding ({}): {}", name, e))?,
+            );
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|e| format!("invalid file name ({}): {}", name, e))
+}
@@ -1,5 +1,5 @@
 use packages::{components::Component, components::Components, depend_types::DependTypes};
-pub use problems::{Problems, report};
+pub use problems::{report, Problems};
 
 pub mod assets;
 #[macro_use]
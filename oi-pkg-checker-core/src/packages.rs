@@ -1,10 +1,28 @@
+pub mod build_env;
+pub mod build_order;
+pub mod checks;
+pub mod closure;
 pub mod components;
 pub mod cycles;
 mod de_serialization;
 pub mod depend_types;
 pub mod dependency_type;
+pub mod files;
+pub mod fix;
+pub mod history;
+pub mod impact;
+pub mod licenses;
+pub mod mediators;
+pub mod orphans;
 pub mod package;
+pub mod publisher_policy;
+pub mod query;
+pub mod renames;
 pub mod rev_depend_type;
+pub mod runtimes;
+pub mod stats;
+pub mod what_if;
+pub mod why;
 
 #[macro_export]
 #[cfg(not(feature = "thread_safe"))]
@@ -114,6 +132,6 @@ macro_rules! get {
 #[cfg(feature = "thread_safe")]
 macro_rules! get_mut {
     ($shared:expr) => {
-        get!($shared)
+        $crate::get!($shared)
     };
 }
@@ -0,0 +1,77 @@
+use fmri::FMRI;
+
+use crate::{get, packages::components::Components, DependTypes};
+
+/// Packages which have to be installed to build component
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildEnv {
+    /// runtime closure of build (and test) dependencies
+    pub packages: Vec<FMRI>,
+    /// publisher of component packages
+    pub publisher: Option<String>,
+    /// packages from other publisher than component
+    pub other_publisher: Vec<FMRI>,
+    pub obsolete: Vec<FMRI>,
+    /// packages of component itself (component has to be bootstrapped)
+    pub own: Vec<FMRI>,
+    /// package and its dependency which can not be resolved
+    pub unresolvable: Vec<(FMRI, DependTypes)>,
+}
+
+impl Components {
+    /// Computes packages needed to build component, test dependencies are included if `test` is true
+    pub fn get_build_env(&self, component_name: &String, test: bool) -> Result<BuildEnv, String> {
+        let component = get!(self
+            .get_component_by_name(component_name)
+            .map_err(|e| format!("failed to get component: {}", e))?);
+
+        let mut dependencies = component.get_build_dependencies().clone();
+        dependencies.extend(component.get_sys_build_dependencies().clone());
+        if test {
+            dependencies.extend(component.get_test_dependencies().clone());
+            dependencies.extend(component.get_sys_test_dependencies().clone());
+        }
+
+        let dependencies = dependencies
+            .iter()
+            .map(|p| get!(p.upgrade().unwrap()).fmri.clone())
+            .collect::<Vec<FMRI>>();
+
+        let publisher = component.get_publisher().map(|p| p.get_as_string());
+
+        drop(component);
+
+        let closure = self
+            .get_install_closure(&dependencies)
+            .map_err(|e| format!("failed to compute closure: {}", e))?;
+
+        let other_publisher = match &publisher {
+            None => Vec::new(),
+            Some(publisher) => closure
+                .packages
+                .iter()
+                .filter(|f| {
+                    f.get_publisher_as_ref_string()
+                        .is_some_and(|p| p != publisher)
+                })
+                .cloned()
+                .collect(),
+        };
+
+        let own = closure
+            .packages
+            .iter()
+            .filter(|f| self.get_owning_component(f).as_ref() == Some(component_name))
+            .cloned()
+            .collect();
+
+        Ok(BuildEnv {
+            packages: closure.packages,
+            publisher,
+            other_publisher,
+            obsolete: closure.obsolete,
+            own,
+            unresolvable: closure.unresolvable,
+        })
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+
+use fmri::FMRI;
+
+use crate::{
+    get,
+    packages::{
+        components::Components,
+        cycles::{strongly_connected_components, EdgeType, Or},
+        depend_types::DependTypes,
+    },
+};
+
+/// Components which depend on each other and have to be bootstrapped by hand
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildCycle {
+    pub components: Vec<String>,
+    /// shortest cycles in component build graph going through these components
+    pub paths: Vec<Vec<(Or, EdgeType)>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildOrder {
+    /// components in one wave can be built in parallel, their dependencies are in previous waves
+    pub waves: Vec<Vec<String>>,
+    pub cycles: Vec<BuildCycle>,
+}
+
+impl Components {
+    /// Returns components needed for build of every component.
+    ///
+    /// Component needs component which contains any of its build or system-build dependencies
+    /// or any package from their runtime closure.
+    pub fn get_component_build_graph(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        for c in &self.components {
+            let component = get!(c);
+            let name = component.get_name().clone();
+
+            let mut queue: VecDeque<FMRI> = component
+                .get_build_dependencies()
+                .iter()
+                .chain(component.get_sys_build_dependencies())
+                .map(|p| get!(p.upgrade().unwrap()).fmri.clone())
+                .collect();
+            drop(component);
+
+            let mut visited: HashSet<String> = HashSet::new();
+            let mut needed: BTreeSet<String> = BTreeSet::new();
+
+            while let Some(fmri) = queue.pop_front() {
+                if !visited.insert(fmri.get_package_name_as_ref_string().clone()) {
+                    continue;
+                }
+
+                let package = match self.get_package_by_fmri(&fmri) {
+                    Ok(p) => get!(p),
+                    Err(_) => continue,
+                };
+
+                if package.is_obsolete() {
+                    continue;
+                }
+
+                if let Some(c) = package.is_in_component() {
+                    let owner = get!(c).get_name().clone();
+                    if owner != name {
+                        needed.insert(owner);
+                    }
+                }
+
+                for dependency in package
+                    .get_effective_version()
+                    .map(|v| v.get_runtime_dependencies().clone())
+                    .unwrap_or_default()
+                {
+                    match dependency {
+                        DependTypes::Require(f) | DependTypes::Group(f) => queue.push_back(f),
+                        DependTypes::RequireAny(l) => {
+                            if let Some(f) = l
+                                .get()
+                                .into_iter()
+                                .find(|f| self.get_package_by_fmri(f).is_ok())
+                            {
+                                queue.push_back(f)
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            graph.insert(name, needed);
+        }
+
+        graph
+    }
+
+    /// Sorts components into build waves, components in cycle are put into the same wave
+    pub fn get_build_order(&self) -> BuildOrder {
+        let graph = self.get_component_build_graph();
+        let sccs = strongly_connected_components(&graph);
+
+        let mut scc_of: BTreeMap<&String, usize> = BTreeMap::new();
+        for (i, scc) in sccs.iter().enumerate() {
+            for name in scc {
+                scc_of.insert(name, i);
+            }
+        }
+
+        // dependencies between strongly connected components
+        let mut needs: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); sccs.len()];
+        for (name, needed) in &graph {
+            let a = scc_of[name];
+            for b in needed.iter().map(|n| scc_of[n]).filter(|b| *b != a) {
+                needs[a].insert(b);
+            }
+        }
+
+        let mut wave_of: Vec<Option<usize>> = vec![None; sccs.len()];
+        let mut waves: Vec<Vec<String>> = Vec::new();
+        while wave_of.iter().any(|w| w.is_none()) {
+            let wave = (0..sccs.len())
+                .filter(|i| wave_of[*i].is_none())
+                .filter(|i| needs[*i].iter().all(|n| wave_of[*n].is_some()))
+                .collect::<Vec<usize>>();
+
+            for i in &wave {
+                wave_of[*i] = Some(waves.len());
+            }
+
+            let mut names = wave
+                .iter()
+                .flat_map(|i| sccs[*i].clone())
+                .collect::<Vec<String>>();
+            names.sort();
+            waves.push(names);
+        }
+
+        let mut cycles = sccs
+            .into_iter()
+            .filter(|scc| scc.len() > 1)
+            .map(|mut scc| {
+                scc.sort();
+                BuildCycle {
+                    paths: cycle_paths(&graph, &scc),
+                    components: scc,
+                }
+            })
+            .collect::<Vec<BuildCycle>>();
+
+        cycles.sort_by(|a, b| a.components.cmp(&b.components));
+
+        BuildOrder { waves, cycles }
+    }
+}
+
+/// Returns the shortest cycle through every component of strongly connected component `scc`,
+/// every edge is reported as [`EdgeType::Build`]
+fn cycle_paths(
+    graph: &BTreeMap<String, BTreeSet<String>>,
+    scc: &[String],
+) -> Vec<Vec<(Or, EdgeType)>> {
+    let mut paths: Vec<Vec<String>> = Vec::new();
+
+    for start in scc {
+        // breadth-first search from start back to start inside of scc
+        let mut previous: BTreeMap<&String, &String> = BTreeMap::new();
+        let mut queue: VecDeque<&String> = VecDeque::from([start]);
+
+        'search: while let Some(name) = queue.pop_front() {
+            for next in graph.get(name).into_iter().flatten() {
+                if !scc.contains(next) || previous.contains_key(next) {
+                    continue;
+                }
+                previous.insert(next, name);
+                if next == start {
+                    break 'search;
+                }
+                queue.push_back(next);
+            }
+        }
+
+        let mut path: Vec<String> = Vec::new();
+        let mut name = start;
+        while let Some(p) = previous.get(name) {
+            path.push((*p).clone());
+            name = p;
+            if name == start {
+                break;
+            }
+        }
+        path.reverse();
+
+        // the same cycle is found from all its components
+        let first = (0..path.len()).min_by_key(|i| &path[*i]).unwrap_or(0);
+        path.rotate_left(first);
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    paths
+        .into_iter()
+        .map(|path| {
+            path.into_iter()
+                .map(|name| (Or::Component(name), EdgeType::Build))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,45 @@
+use crate::{packages::publisher_policy::PublisherPolicy, problems::Problems, Components};
+
+/// Options of checks run by [`Components::check_all`]
+#[derive(Clone, Debug, Default)]
+pub struct CheckOptions {
+    /// allowed dependencies between publishers
+    pub publisher_policy: PublisherPolicy,
+}
+
+impl Components {
+    /// Runs all checks of loaded data, checks of paths, links, mediators and licenses are run only
+    /// if actions of packages are kept (see [`Components::set_keep_actions`])
+    pub fn check_all(&mut self, options: &CheckOptions) -> Result<(), String> {
+        self.check_problems()
+            .map_err(|e| format!("failed to check problems: {}", e))?;
+        self.check_publisher_policy(&options.publisher_policy)
+            .map_err(|e| format!("failed to check publisher policy: {}", e))?;
+
+        if self.are_actions_kept() {
+            self.check_path_conflicts()
+                .map_err(|e| format!("failed to check path conflicts: {}", e))?;
+            self.check_dangling_links()
+                .map_err(|e| format!("failed to check links: {}", e))?;
+            self.check_mediators()
+                .map_err(|e| format!("failed to check mediators: {}", e))?;
+            self.check_licenses()
+                .map_err(|e| format!("failed to check licenses: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes problems which [`Components::check_all`] finds in current data, problems found
+    /// while data was loaded are kept
+    pub(crate) fn remove_checked_problems(&mut self, options: &CheckOptions) -> Result<(), String> {
+        let mut checked = self.deep_clone();
+        checked.problems = Problems::new();
+        checked.check_all(options)?;
+
+        let found = checked.problems.get_ref();
+        self.problems.retain(|p| !found.contains(p));
+
+        Ok(())
+    }
+}
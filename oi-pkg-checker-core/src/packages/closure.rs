@@ -0,0 +1,149 @@
+use std::collections::{btree_map::Entry, BTreeMap, VecDeque};
+
+use fmri::FMRI;
+
+use crate::{get, packages::components::Components, DependTypes};
+
+/// Packages which are installed together with requested packages
+#[derive(Clone, Debug, PartialEq)]
+pub struct Closure {
+    /// all packages in closure (including requested ones)
+    pub packages: Vec<FMRI>,
+    pub obsolete: Vec<FMRI>,
+    pub renamed: Vec<FMRI>,
+    /// package and its dependency which can not be resolved
+    pub unresolvable: Vec<(FMRI, DependTypes)>,
+}
+
+impl Components {
+    /// Computes packages pulled in by installing `fmris`.
+    ///
+    /// `require` and `group` dependencies are followed, the first satisfiable alternative of
+    /// `require-any` is picked (unless some alternative is already in closure) and `conditional`
+    /// dependencies are followed if their predicate is in closure.
+    pub fn get_install_closure(&self, fmris: &[FMRI]) -> Result<Closure, String> {
+        let mut closure: BTreeMap<String, FMRI> = BTreeMap::new();
+        let mut queue: VecDeque<FMRI> = VecDeque::new();
+        let mut pending: Vec<(FMRI, DependTypes)> = Vec::new();
+        let mut unresolvable: Vec<(FMRI, DependTypes)> = Vec::new();
+
+        let add = |closure: &mut BTreeMap<String, FMRI>,
+                   queue: &mut VecDeque<FMRI>,
+                   fmri: &FMRI|
+         -> bool {
+            let package = match self.get_package_by_fmri(fmri) {
+                Ok(p) => get!(p).fmri.clone(),
+                Err(_) => return false,
+            };
+
+            let name = package.get_package_name_as_ref_string().clone();
+            if let Entry::Vacant(entry) = closure.entry(name) {
+                entry.insert(package.clone());
+                queue.push_back(package);
+            }
+            true
+        };
+
+        for fmri in fmris {
+            if !add(&mut closure, &mut queue, fmri) {
+                return Err(format!("package {} does not exist", fmri));
+            }
+        }
+
+        loop {
+            while let Some(fmri) = queue.pop_front() {
+                let dependencies = get!(self.get_package_by_fmri(&fmri)?)
+                    .get_effective_version()
+                    .map(|v| v.get_runtime_dependencies().clone())
+                    .unwrap_or_default();
+
+                for d in dependencies {
+                    match &d {
+                        DependTypes::Require(f) | DependTypes::Group(f) => {
+                            let added = add(&mut closure, &mut queue, f);
+                            if !added {
+                                unresolvable.push((fmri.clone(), d.clone()))
+                            }
+                        }
+                        DependTypes::RequireAny(_) | DependTypes::Conditional(_, _) => {
+                            pending.push((fmri.clone(), d.clone()))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let in_closure = |closure: &BTreeMap<String, FMRI>, f: &FMRI| {
+                closure.contains_key(f.get_package_name_as_ref_string())
+            };
+
+            // drop satisfied require-any and follow conditionals with satisfied predicate
+            let mut index = 0;
+            while index < pending.len() {
+                let (fmri, d) = &pending[index];
+                match d {
+                    DependTypes::RequireAny(l)
+                        if l.get_ref().iter().any(|f| in_closure(&closure, f)) => {}
+                    DependTypes::Conditional(f, predicate) if in_closure(&closure, predicate) => {
+                        if !add(&mut closure, &mut queue, f) {
+                            unresolvable.push((fmri.clone(), d.clone()))
+                        }
+                    }
+                    _ => {
+                        index += 1;
+                        continue;
+                    }
+                }
+                pending.remove(index);
+            }
+
+            if !queue.is_empty() {
+                continue;
+            }
+
+            // nothing else to follow, pick alternative of the first unsatisfied require-any
+            let position = pending
+                .iter()
+                .position(|(_, d)| matches!(d, DependTypes::RequireAny(_)));
+
+            let (fmri, d) = match position {
+                Some(position) => pending.remove(position),
+                None => break,
+            };
+
+            if let DependTypes::RequireAny(l) = &d {
+                let alternative = l.get_ref().iter().find(|f| {
+                    self.get_package_by_fmri(f)
+                        .is_ok_and(|p| !get!(p).is_obsolete())
+                });
+
+                match alternative {
+                    Some(f) => {
+                        add(&mut closure, &mut queue, f);
+                    }
+                    None => unresolvable.push((fmri, d.clone())),
+                }
+            }
+        }
+
+        let packages = closure.into_values().collect::<Vec<FMRI>>();
+
+        let mut obsolete: Vec<FMRI> = Vec::new();
+        let mut renamed: Vec<FMRI> = Vec::new();
+        for fmri in &packages {
+            let package = get!(self.get_package_by_fmri(fmri)?);
+            if package.is_obsolete() {
+                obsolete.push(fmri.clone())
+            } else if package.is_renamed() {
+                renamed.push(fmri.clone())
+            }
+        }
+
+        Ok(Closure {
+            packages,
+            obsolete,
+            renamed,
+            unresolvable,
+        })
+    }
+}
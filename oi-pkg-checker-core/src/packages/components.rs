@@ -1,10 +1,11 @@
 use std::{
     cmp::Ordering,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Debug,
+    mem::take,
 };
 
-use fmri::{FMRIList, FMRI};
+use fmri::{FMRIList, Publisher, Version, FMRI};
 
 use crate::problems::Problem::SamePackageHasTwoPublishers;
 use crate::{
@@ -15,15 +16,18 @@ use crate::{
             DependencyTypes::{Build, Runtime, SystemBuild, SystemTest, Test},
         },
         package::Package,
+        renames::RenameChain,
         rev_depend_type::{RevDependType, RevDependType::*},
     },
     problems::{
         Problem,
         Problem::{
-            MissingComponentForPackage, NonExistingPackageInPkg5, NonExistingRequired,
-            NonExistingRequiredByRenamed, ObsoletedPackageInComponent, ObsoletedRequired,
-            ObsoletedRequiredByRenamed, PartlyObsoletedRequired, PartlyObsoletedRequiredByRenamed,
-            RenamedNeedsRenamed, RenamedPackageInComponent, UselessComponent,
+            MissingComponentForPackage, MissingRequiredPackage, NonExistingPackageInPkg5,
+            NonExistingRequired, NonExistingRequiredByRenamed, ObsoletedPackageInComponent,
+            ObsoletedRequired, ObsoletedRequiredByRenamed, OwnPackageInRequiredPackages,
+            PartlyObsoletedRequired, PartlyObsoletedRequiredByRenamed, RenameHistoryMismatch,
+            RenameLoop, RenamedNeedsRenamed, RenamedPackageInComponent, RenamedToNonExisting,
+            UselessComponent,
         },
     },
     shared_type, weak_type, DependTypes, Problems,
@@ -38,9 +42,15 @@ pub struct Components {
     pub(crate) packages: Vec<shared_type!(Package)>,
     pub(crate) hash_packages: HashMap<String, shared_type!(Package)>,
     pub problems: Problems,
+    /// keep all versions of packages instead of only the effective one
+    pub(crate) keep_history: bool,
+    /// keep all actions from manifests of packages
+    pub(crate) keep_actions: bool,
 }
 
 impl Components {
+    /// Adds package, if package with the same publisher exists, versions are merged (the same
+    /// version is replaced by the added one)
     pub fn add_package(&mut self, mut package: Package) {
         let package_name = package.fmri.clone().get_package_name_as_string();
 
@@ -54,6 +64,24 @@ impl Components {
             }
         });
 
+        if existing_package.get_publisher() == package.get_publisher() {
+            // the same package from another source (e.g. p5p archive over catalog or second
+            // catalog), versions are merged and version loaded later replaces the same version
+            for version in package.versions {
+                match existing_package
+                    .versions
+                    .iter_mut()
+                    .find(|v| v.version.cmp(&version.version) == Ordering::Equal)
+                {
+                    Some(existing_version) => *existing_version = version,
+                    None => existing_package.versions.push(version),
+                }
+            }
+
+            existing_package.set_state_from_newest_version();
+            return;
+        }
+
         let mut existing_package_versions = existing_package.get_versions().clone();
         existing_package_versions.sort_by(|a, b| a.version.cmp(&b.version));
         package.versions.sort_by(|a, b| a.version.cmp(&b.version));
@@ -67,6 +95,15 @@ impl Components {
                     Ordering::Equal | Ordering::Less => {
                         // everything is ok, old version is obsoleted, but we need to save new version
 
+                        // links from components are kept (package can be added after git is loaded)
+                        package.component = existing_package.component.take();
+                        package.build_dependents = take(&mut existing_package.build_dependents);
+                        package.test_dependents = take(&mut existing_package.test_dependents);
+                        package.sys_build_dependents =
+                            take(&mut existing_package.sys_build_dependents);
+                        package.sys_test_dependents =
+                            take(&mut existing_package.sys_test_dependents);
+
                         *existing_package = package;
                     }
                     Ordering::Greater => {
@@ -163,20 +200,20 @@ impl Components {
     }
 
     pub fn get_component_by_name(&self, name: &String) -> Result<&shared_type!(Component), String> {
-        return match self.hash_components.get(name) {
+        match self.hash_components.get(name) {
             None => Err(format!("component {} does not exist", name)),
             Some(component) => Ok(component),
-        };
+        }
     }
 
     pub fn get_package_by_fmri(&self, fmri: &FMRI) -> Result<&shared_type!(Package), String> {
-        return match self
+        match self
             .hash_packages
             .get(fmri.get_package_name_as_ref_string())
         {
             None => Err(format!("package {} does not exist", fmri)),
             Some(package) => Ok(package),
-        };
+        }
     }
 
     pub fn get_components(&self) -> &Vec<shared_type!(Component)> {
@@ -187,6 +224,24 @@ impl Components {
         &self.packages
     }
 
+    /// if set, loaders keep all versions of packages (see [`Components::remove_old_versions`])
+    pub fn set_keep_history(&mut self, keep_history: bool) {
+        self.keep_history = keep_history
+    }
+
+    pub fn is_history_kept(&self) -> bool {
+        self.keep_history
+    }
+
+    /// if set, loaders of manifests keep all actions of packages (see `PackageVersion::get_actions`)
+    pub fn set_keep_actions(&mut self, keep_actions: bool) {
+        self.keep_actions = keep_actions
+    }
+
+    pub fn are_actions_kept(&self) -> bool {
+        self.keep_actions
+    }
+
     /// adds repo dependencies (Build, Test, System Build and System Test) into component
     pub fn add_repo_dependencies(
         &mut self,
@@ -232,6 +287,209 @@ impl Components {
         Ok(())
     }
 
+    pub fn remove_repo_dependencies(
+        &mut self,
+        component_name: &String,
+        dependencies: Vec<FMRI>,
+        dependency_type: &DependencyTypes,
+    ) -> Result<(), String> {
+        let component = clone!(self
+            .get_component_by_name(component_name)
+            .map_err(|e| format!("failed to get component: {}", e))?);
+
+        for fmri in dependencies {
+            let rc_package = match self.get_package_by_fmri(&fmri) {
+                Ok(p) => p,
+                Err(_) => {
+                    // dependency was reported as non-existing
+                    self.problems.retain(|p| {
+                        !matches!(p, NonExistingRequired(DependTypes::Require(f), d, _, c)
+                            if f.package_name_eq(&fmri) && d == dependency_type && c == component_name)
+                    });
+                    continue;
+                }
+            };
+
+            let mut component_mut = get_mut!(component);
+            let dependencies = match dependency_type {
+                Build => &mut component_mut.build,
+                Test => &mut component_mut.test,
+                SystemBuild => &mut component_mut.sys_build,
+                SystemTest => &mut component_mut.sys_test,
+                Runtime => {
+                    return Err("can not remove runtime dependencies from component".to_owned())
+                }
+            };
+            dependencies.retain(|p| !get!(p.upgrade().unwrap()).fmri.package_name_eq(&fmri));
+            drop(component_mut);
+
+            get_mut!(rc_package)
+                .remove_dependent(component_name, dependency_type)
+                .map_err(|e| format!("failed to remove dependent: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Links packages which were reported as non-existing by components (in pkg5 or as build and
+    /// test dependencies) and exist now, e.g. after loading p5p archive over loaded components
+    pub fn link_added_packages(&mut self) -> Result<(), String> {
+        let added = self
+            .problems
+            .get_ref()
+            .iter()
+            .filter(|p| match p {
+                NonExistingPackageInPkg5(f, _) => self.get_package_by_fmri(f).is_ok(),
+                NonExistingRequired(DependTypes::Require(f), d, _, _) => {
+                    d != &Runtime && self.get_package_by_fmri(f).is_ok()
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect::<Vec<Problem>>();
+        self.problems.retain(|p| !added.contains(p));
+
+        for problem in added {
+            match problem {
+                NonExistingPackageInPkg5(fmri, component_name) => {
+                    let rc_component = clone!(self
+                        .get_component_by_name(&component_name)
+                        .map_err(|e| format!("failed to get component: {}", e))?);
+                    let rc_package = clone!(self.get_package_by_fmri(&fmri)?);
+
+                    get_mut!(rc_component).add_package(downgrade!(&rc_package));
+                    let res = get_mut!(rc_package).set_component(rc_component);
+                    if let Some(p) = res {
+                        self.problems.add_problem(*p);
+                    }
+                }
+                NonExistingRequired(DependTypes::Require(fmri), d_type, _, component_name) => {
+                    self.add_repo_dependencies(&component_name, vec![fmri], &d_type)?
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes package from the system (package is deleted from pkg5 repository)
+    pub fn remove_package(&mut self, fmri: &FMRI) -> Result<(), String> {
+        let rc_package = clone!(self
+            .get_package_by_fmri(fmri)
+            .map_err(|e| format!("failed to get package: {}", e))?);
+
+        let (fmri, runtime_dependents, dependencies) = {
+            let package = get!(rc_package);
+            (
+                package.fmri.clone(),
+                package.runtime_dependents.clone(),
+                package
+                    .get_effective_version()
+                    .map(|v| v.runtime.clone())
+                    .unwrap_or_default(),
+            )
+        };
+
+        // remove package from components
+        for c in &self.components {
+            let mut component_mut = get_mut!(c);
+            let component = &mut *component_mut;
+            let name = component.name.clone();
+            let is_removed =
+                |p: &weak_type!(Package)| get!(p.upgrade().unwrap()).fmri.package_name_eq(&fmri);
+
+            component.packages.retain(|p| !is_removed(p));
+
+            for (dependencies, dependency_type) in [
+                (&mut component.build, Build),
+                (&mut component.test, Test),
+                (&mut component.sys_build, SystemBuild),
+                (&mut component.sys_test, SystemTest),
+            ] {
+                if dependencies.iter().any(is_removed) {
+                    dependencies.retain(|p| !is_removed(p));
+                    self.problems.add_problem(NonExistingRequired(
+                        DependTypes::Require(fmri.clone()),
+                        dependency_type,
+                        FMRI::parse_raw("none").unwrap(),
+                        name.clone(),
+                    ));
+                }
+            }
+        }
+
+        self.packages
+            .retain(|p| !get!(p).fmri.package_name_eq(&fmri));
+        self.hash_packages
+            .remove(&fmri.clone().get_package_name_as_string());
+
+        // remove reverse dependencies of removed package
+        for d in dependencies {
+            let required = match d {
+                DependTypes::Require(f)
+                | DependTypes::Optional(f)
+                | DependTypes::Incorporate(f)
+                | DependTypes::Group(f) => vec![f],
+                DependTypes::RequireAny(l) => l.get(),
+                DependTypes::Conditional(f, p) => vec![f, p],
+                _ => continue,
+            };
+
+            for f in required {
+                if let Ok(p) = self.get_package_by_fmri(&f) {
+                    get_mut!(p).runtime_dependents.retain(|r| match r {
+                        Require(f)
+                        | Optional(f)
+                        | Incorporate(f)
+                        | RequireAny(f)
+                        | ConditionalFmri(f)
+                        | ConditionalPredicate(f)
+                        | Group(f) => !f.package_name_eq(&fmri),
+                    });
+                }
+            }
+        }
+
+        // packages which required removed package
+        for rev_dep in runtime_dependents {
+            let (f, d_type) = required_dependency(rev_dep, &fmri);
+
+            if f.package_name_eq(&fmri) {
+                continue;
+            }
+
+            let renamed = get!(self.get_package_by_fmri(&f)?).is_renamed();
+            self.problems.add_problem(match renamed {
+                true => NonExistingRequiredByRenamed(d_type, Runtime, f),
+                false => NonExistingRequired(d_type, Runtime, f, "".to_owned()),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Removes version of package, package without versions is removed (see [`Components::remove_package`]),
+    /// otherwise obsolete and renamed flags of package are set from its newest remaining version
+    pub fn remove_package_version(&mut self, fmri: &FMRI, version: &Version) -> Result<(), String> {
+        let is_empty = match self.get_package_by_fmri(fmri) {
+            Ok(package) => {
+                let mut package = get_mut!(package);
+                package.versions.retain(|v| &v.version != version);
+                package.set_state_from_newest_version();
+                package.versions.is_empty()
+            }
+            // package is not known (e.g. it was added and removed between catalog updates)
+            Err(_) => return Ok(()),
+        };
+
+        if is_empty {
+            self.remove_package(fmri)?;
+        }
+
+        Ok(())
+    }
+
     pub fn set_package_obsolete(&mut self, fmri: FMRI) -> Result<(), String> {
         let mut fmri_clone = fmri.clone();
         let rc_package = self
@@ -252,16 +510,49 @@ impl Components {
         Ok(())
     }
 
-    pub fn set_package_renamed(&mut self, fmri: FMRI) -> Result<(), String> {
+    /// Marks package and all its versions obsolete and not renamed, as if obsolete version of
+    /// package was published (used for impact analysis and what-if plans)
+    pub fn obsolete_whole_package(&mut self, fmri: &FMRI) -> Result<(), String> {
+        let rc_package = self
+            .get_package_by_fmri(fmri)
+            .map_err(|e| format!("failed to get package: {}", e))?;
+
+        let mut package = get_mut!(rc_package);
+        package.set_obsolete(true);
+        package.set_renamed(false);
+        for version in package.get_versions_mut() {
+            version.set_obsolete(true);
+            version.set_renamed(false);
+        }
+
+        Ok(())
+    }
+
+    /// `renamed_to` is used only if rename target is not known from catalog
+    pub fn set_package_renamed(
+        &mut self,
+        fmri: FMRI,
+        renamed_to: Option<FMRI>,
+    ) -> Result<(), String> {
         let mut fmri_clone = fmri.clone();
         let rc_package = self
             .get_package_by_fmri(fmri_clone.remove_version())
             .map_err(|e| format!("failed to get package: {}", e))?;
 
+        let mut package = get_mut!(rc_package);
+
         match fmri.get_version() {
-            None => get_mut!(rc_package).set_renamed(true),
+            None => {
+                // whole package is renamed, renamed package is not obsolete
+                package.set_renamed(true);
+                package.set_obsolete(false);
+                for version in package.get_versions_mut() {
+                    version.set_renamed(true);
+                    version.set_obsolete(false);
+                }
+            }
             Some(fmri_version) => {
-                for version in get_mut!(rc_package).get_versions_mut() {
+                for version in package.get_versions_mut() {
                     if version.version == fmri_version {
                         version.set_renamed(true);
                     }
@@ -269,11 +560,58 @@ impl Components {
             }
         }
 
+        if package.is_renamed() && package.get_renamed_to().is_none() {
+            package.set_renamed_to(renamed_to);
+        }
+
+        Ok(())
+    }
+
+    /// Compares rename from history file of component with catalog
+    pub fn check_history_rename(
+        &mut self,
+        fmri: &FMRI,
+        renamed_to: &FMRI,
+        component_name: &str,
+    ) -> Result<(), String> {
+        let catalog_renamed_to = {
+            let package = get!(self
+                .get_package_by_fmri(fmri)
+                .map_err(|e| format!("failed to get package: {}", e))?);
+
+            if !package.is_renamed() {
+                None
+            } else {
+                package.get_renamed_to().clone()
+            }
+        };
+
+        match &catalog_renamed_to {
+            Some(c) if c.package_name_eq(renamed_to) => {}
+            _ => self.problems.add_problem(RenameHistoryMismatch(
+                fmri.clone(),
+                catalog_renamed_to,
+                renamed_to.clone(),
+                component_name.to_owned(),
+            )),
+        }
+
         Ok(())
     }
 
     // TODO: there might be something wrong here
     pub fn distribute_reverse_runtime_dependencies(&mut self) {
+        // dependents are computed again when packages are added by another loader
+        for p in &self.packages {
+            get_mut!(p).runtime_dependents.clear();
+        }
+        self.problems.retain(|p| {
+            !matches!(
+                p,
+                NonExistingRequired(_, Runtime, _, _) | NonExistingRequiredByRenamed(_, Runtime, _)
+            )
+        });
+
         let mut rev_run_deps: HashMap<FMRI, HashSet<RevDependType>> = HashMap::new();
 
         let mut add = |fmri: FMRI, rev_depend_type: RevDependType| {
@@ -285,7 +623,7 @@ impl Components {
 
         for p in &*self.packages {
             let package = get!(p);
-            for version in &package.versions {
+            if let Some(version) = package.get_effective_version() {
                 for d in &version.runtime {
                     match d.clone() {
                         DependTypes::Require(f) => add(f, Require(package.fmri.clone())),
@@ -317,30 +655,7 @@ impl Components {
                 Ok(package) => get_mut!(package).runtime_dependents.append(&mut rev_deps),
                 Err(_) => {
                     for rev_dep in rev_deps {
-                        let (f, d_type) = match rev_dep {
-                            Require(f) => (f, DependTypes::Require(fmri.clone())),
-                            Optional(f) => (f, DependTypes::Optional(fmri.clone())),
-                            Incorporate(f) => (f, DependTypes::Incorporate(fmri.clone())),
-                            RequireAny(f) => (
-                                f,
-                                DependTypes::RequireAny(FMRIList::from(vec![fmri.clone()])),
-                            ),
-                            ConditionalFmri(f) => (
-                                f,
-                                DependTypes::Conditional(
-                                    fmri.clone(),
-                                    FMRI::parse_raw("none").unwrap(),
-                                ),
-                            ),
-                            ConditionalPredicate(f) => (
-                                f,
-                                DependTypes::Conditional(
-                                    FMRI::parse_raw("none").unwrap(),
-                                    fmri.clone(),
-                                ),
-                            ),
-                            Group(f) => (f, DependTypes::Group(fmri.clone())),
-                        };
+                        let (f, d_type) = required_dependency(rev_dep, &fmri);
 
                         self.problems
                             .add_problem(match self.get_package_by_fmri(&f) {
@@ -362,21 +677,21 @@ impl Components {
         for p in &mut self.packages {
             let mut package = get_mut!(p);
 
-            package.versions.sort_by(|a, b| b.version.cmp(&a.version));
-
-            let mut new_ver = package.versions.first().unwrap().clone();
-
-            for ver in &package.versions {
-                if !ver.is_obsolete() && !ver.is_renamed() {
-                    new_ver = ver.clone();
-                    break;
-                }
-            }
+            let new_ver = package.get_effective_version().unwrap().clone();
 
             package.change_versions(vec![new_ver]);
         }
     }
 
+    /// sorts versions of every package from the newest to the oldest
+    pub fn sort_versions(&mut self) {
+        for p in &mut self.packages {
+            get_mut!(p)
+                .versions
+                .sort_by(|a, b| b.version.cmp(&a.version));
+        }
+    }
+
     pub fn check_problems(&mut self) -> Result<(), String> {
         // ObsoletedPackageInComponent and RenamedPackageInComponent
         for c in &*self.components {
@@ -545,6 +860,94 @@ impl Components {
             }
         }
 
+        // RenamedToNonExisting and RenameLoop
+        let renamed_packages = self
+            .packages
+            .iter()
+            .map(|p| get!(p))
+            .filter(|p| p.is_renamed())
+            .map(|p| p.fmri.clone())
+            .collect::<Vec<FMRI>>();
+
+        for fmri in renamed_packages {
+            match self.get_rename_chain(&fmri)? {
+                RenameChain::NonExisting(chain) => {
+                    let mut chain = chain.into_iter().rev();
+                    let non_existing = chain.next().unwrap();
+                    self.problems
+                        .add_problem(RenamedToNonExisting(chain.next().unwrap(), non_existing));
+                }
+                RenameChain::Loop(rename_loop) => {
+                    self.problems.add_problem(RenameLoop(rename_loop))
+                }
+                RenameChain::Resolved(_) | RenameChain::Unknown(_) => {}
+            }
+        }
+
+        // MissingRequiredPackage and OwnPackageInRequiredPackages
+        for c in &*self.components {
+            let component = get!(c);
+            let name_of =
+                |p: &weak_type!(Package)| -> FMRI { get!(p.upgrade().unwrap()).fmri.clone() };
+
+            let own = component
+                .packages
+                .iter()
+                .map(name_of)
+                .collect::<Vec<FMRI>>();
+            let required = component.build.iter().map(name_of).collect::<Vec<FMRI>>();
+
+            for fmri in required
+                .iter()
+                .filter(|f| own.iter().any(|o| o.package_name_eq(f)))
+            {
+                self.problems.add_problem(OwnPackageInRequiredPackages(
+                    fmri.clone(),
+                    component.name.clone(),
+                ));
+            }
+
+            let mut dependencies: Vec<FMRI> = Vec::new();
+            for p in &component.packages {
+                let t = p.upgrade().unwrap();
+                let package = get!(t);
+
+                if package.is_obsolete() || package.is_renamed() {
+                    continue;
+                }
+
+                for d in package
+                    .get_effective_version()
+                    .map(|v| v.get_runtime_dependencies().clone())
+                    .unwrap_or_default()
+                {
+                    if let DependTypes::Require(f) = d {
+                        dependencies.push(f)
+                    }
+                }
+            }
+
+            for fmri in dependencies {
+                if own
+                    .iter()
+                    .chain(&required)
+                    .any(|f| f.package_name_eq(&fmri))
+                {
+                    continue;
+                }
+
+                let published = self.get_package_by_fmri(&fmri).is_ok_and(|p| {
+                    let package = get!(p);
+                    !package.is_obsolete() && !package.is_renamed()
+                });
+
+                if published {
+                    self.problems
+                        .add_problem(MissingRequiredPackage(fmri, component.name.clone()));
+                }
+            }
+        }
+
         // ObsoletedRequired, ObsoletedRequiredByRenamed, PartlyObsoletedRequired, PartlyObsoletedRequiredByRenamed
         for p in &self.packages.clone() {
             let package = get!(p);
@@ -553,7 +956,7 @@ impl Components {
                 continue;
             }
 
-            if package.versions.first().unwrap().is_obsolete() {
+            if package.get_effective_version().unwrap().is_obsolete() {
                 check_obsoleted_required_packages(
                     self,
                     &package,
@@ -631,6 +1034,28 @@ fn check_obsoleted_required_packages(
     }
 }
 
+/// Returns package which has dependency `rev_dep` and that dependency on `fmri`
+fn required_dependency(rev_dep: RevDependType, fmri: &FMRI) -> (FMRI, DependTypes) {
+    match rev_dep {
+        Require(f) => (f, DependTypes::Require(fmri.clone())),
+        Optional(f) => (f, DependTypes::Optional(fmri.clone())),
+        Incorporate(f) => (f, DependTypes::Incorporate(fmri.clone())),
+        RequireAny(f) => (
+            f,
+            DependTypes::RequireAny(FMRIList::from(vec![fmri.clone()])),
+        ),
+        ConditionalFmri(f) => (
+            f,
+            DependTypes::Conditional(fmri.clone(), FMRI::parse_raw("none").unwrap()),
+        ),
+        ConditionalPredicate(f) => (
+            f,
+            DependTypes::Conditional(FMRI::parse_raw("none").unwrap(), fmri.clone()),
+        ),
+        Group(f) => (f, DependTypes::Group(fmri.clone())),
+    }
+}
+
 /// Component contains name, list of packages in component and dependencies.
 #[derive(Clone, Debug)]
 pub struct Component {
@@ -642,6 +1067,9 @@ pub struct Component {
     pub(crate) test: Vec<weak_type!(Package)>,
     pub(crate) sys_build: Vec<weak_type!(Package)>,
     pub(crate) sys_test: Vec<weak_type!(Package)>,
+    /// COMPONENT_LICENSE and COMPONENT_LICENSE_FILE from Makefile, only if licenses are loaded
+    pub(crate) license: Option<String>,
+    pub(crate) license_file: Option<String>,
 }
 
 impl Component {
@@ -653,6 +1081,8 @@ impl Component {
             test: Vec::new(),
             sys_build: Vec::new(),
             sys_test: Vec::new(),
+            license: None,
+            license_file: None,
         }
     }
 
@@ -679,4 +1109,32 @@ impl Component {
     pub fn get_sys_test_dependencies(&self) -> &Vec<weak_type!(Package)> {
         &self.sys_test
     }
+
+    pub fn set_license(&mut self, license: Option<String>, license_file: Option<String>) {
+        self.license = license;
+        self.license_file = license_file;
+    }
+
+    pub fn get_license(&self) -> &Option<String> {
+        &self.license
+    }
+
+    pub fn get_license_file(&self) -> &Option<String> {
+        &self.license_file
+    }
+
+    /// Returns the most common publisher of component packages
+    pub fn get_publisher(&self) -> Option<Publisher> {
+        let mut publishers: BTreeMap<Publisher, usize> = BTreeMap::new();
+        for p in &self.packages {
+            if let Some(publisher) = get!(p.upgrade().unwrap()).get_publisher() {
+                *publishers.entry(publisher.clone()).or_default() += 1;
+            }
+        }
+
+        publishers
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(publisher, _)| publisher)
+    }
 }
@@ -1,4 +1,7 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashSet},
+};
 
 use fmri::FMRI;
 use log::warn;
@@ -30,7 +33,7 @@ pub enum Or {
 }
 
 impl Components {
-    pub fn check_cycles(&mut self, edge_types: &[EdgeType]) -> Vec<Vec<(Or, EdgeType)>> {
+    pub fn check_cycles(&self, edge_types: &[EdgeType]) -> Vec<Vec<(Or, EdgeType)>> {
         let mut cycles: Vec<Vec<(FMRI, EdgeType)>> = Vec::new();
         let mut visited: Vec<FMRI> = Vec::new();
 
@@ -80,15 +83,14 @@ impl Components {
 
             for (i, (or, t)) in cycle.iter().enumerate() {
                 match t {
-                    RuntimeRequire | RuntimeRequireAny | RuntimeConditionalFmri => {
+                    RuntimeRequire | RuntimeRequireAny | RuntimeConditionalFmri
                         if &match cycle.get(i + 1) {
                             None => cycle.first().unwrap(),
                             Some(a) => a,
                         }
-                        .0 == or
-                        {
-                            continue;
-                        }
+                        .0 == or =>
+                    {
+                        continue;
                     }
                     _ => {}
                 }
@@ -140,8 +142,7 @@ fn find_cycles(
     };
 
     for r_d in package
-        .get_versions()
-        .first()
+        .get_effective_version()
         .unwrap()
         .get_runtime_dependencies()
     {
@@ -319,3 +320,68 @@ impl PartialEq for Or {
         }
     }
 }
+
+/// Tarjan's algorithm, returns components of graph which depend on each other.
+///
+/// Every strongly connected component is returned after all components reachable from it.
+pub(crate) fn strongly_connected_components(
+    graph: &BTreeMap<String, BTreeSet<String>>,
+) -> Vec<Vec<String>> {
+    struct State<'a> {
+        graph: &'a BTreeMap<String, BTreeSet<String>>,
+        index: BTreeMap<&'a String, usize>,
+        low_link: BTreeMap<&'a String, usize>,
+        stack: Vec<&'a String>,
+        on_stack: HashSet<&'a String>,
+        sccs: Vec<Vec<String>>,
+    }
+
+    fn visit<'a>(state: &mut State<'a>, node: &'a String) {
+        let index = state.index.len();
+        state.index.insert(node, index);
+        state.low_link.insert(node, index);
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        for next in state.graph.get(node).into_iter().flatten() {
+            if !state.index.contains_key(next) {
+                visit(state, next);
+                let low = state.low_link[node].min(state.low_link[next]);
+                state.low_link.insert(node, low);
+            } else if state.on_stack.contains(next) {
+                let low = state.low_link[node].min(state.index[next]);
+                state.low_link.insert(node, low);
+            }
+        }
+
+        if state.low_link[node] == state.index[node] {
+            let mut scc: Vec<String> = Vec::new();
+            loop {
+                let n = state.stack.pop().unwrap();
+                state.on_stack.remove(n);
+                scc.push(n.clone());
+                if n == node {
+                    break;
+                }
+            }
+            state.sccs.push(scc);
+        }
+    }
+
+    let mut state = State {
+        graph,
+        index: BTreeMap::new(),
+        low_link: BTreeMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        sccs: Vec::new(),
+    };
+
+    for node in graph.keys() {
+        if !state.index.contains_key(node) {
+            visit(&mut state, node);
+        }
+    }
+
+    state.sccs
+}
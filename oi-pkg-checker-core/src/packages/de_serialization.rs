@@ -18,11 +18,18 @@ use crate::{
     shared_type, weak_type, Component as OrgComponent, Components as OrgComponents, Problems,
 };
 
+/// marks serialized data, data without it are from version which did not have format version
+const DATA_MAGIC: u32 = 0x6f69_7063;
+/// version of format of serialized data, it has to be raised when serialized data change
+const DATA_FORMAT_VERSION: u32 = 10;
+
 #[derive(Serialize, Deserialize)]
 pub struct Components {
     components: Vec<Component>,
     packages: Vec<Package>,
     problems: Problems,
+    keep_history: bool,
+    keep_actions: bool,
 }
 #[derive(Serialize, Deserialize)]
 pub struct Component {
@@ -32,6 +39,8 @@ pub struct Component {
     test: Vec<FMRI>,
     sys_build: Vec<FMRI>,
     sys_test: Vec<FMRI>,
+    license: Option<String>,
+    license_file: Option<String>,
 }
 #[derive(Serialize, Deserialize)]
 pub struct Package {
@@ -40,6 +49,7 @@ pub struct Package {
     component: Option<String>,
     obsolete: bool,
     renamed: bool,
+    renamed_to: Option<FMRI>,
     runtime_dependents: Vec<RevDependType>,
     build_dependents: Vec<String>,
     test_dependents: Vec<String>,
@@ -54,11 +64,49 @@ impl OrgComponents {
             .map_err(|e| format!("failed to open file {}: {}", path, e))?
             .read_to_end(data)
             .map_err(|e| format!("failed to read file: {}", e))?;
-        let components: Components =
+
+        // data created by other version of oi-pkg-checker can not be deserialized
+        let (magic, version): (u32, u32) = deserialize(data).unwrap_or_default();
+        if magic != DATA_MAGIC {
+            return Err(
+                "data was created by older version of oi-pkg-checker, re-run `run` to create it again"
+                    .to_owned(),
+            );
+        }
+        if version != DATA_FORMAT_VERSION {
+            return Err(format!(
+                "data has format version {} instead of {}, re-run `run` to create it again",
+                version, DATA_FORMAT_VERSION
+            ));
+        }
+
+        let (_, _, components): (u32, u32, Components) =
             deserialize(data).map_err(|e| format!("failed to deserialize data: {}", e))?;
 
+        Ok(Self::from_serializable(components))
+    }
+
+    pub fn serialize<P: AsRef<Path> + ?Sized + Display>(&self, path: &P) -> Result<(), String> {
+        File::create(path)
+            .map_err(|e| format!("failed to create file {}: {}", path, e))?
+            .write_all(
+                &serialize(&(DATA_MAGIC, DATA_FORMAT_VERSION, self.to_serializable()))
+                    .map_err(|e| format!("failed to serialize components: {}", e))?,
+            )
+            .map_err(|e| format!("failed to write serialized data into file: {}", e))?;
+        Ok(())
+    }
+
+    /// Returns copy which does not share packages and components with original
+    pub fn deep_clone(&self) -> Self {
+        Self::from_serializable(self.to_serializable())
+    }
+
+    fn from_serializable(components: Components) -> Self {
         let mut org_components = OrgComponents {
             problems: components.problems,
+            keep_history: components.keep_history,
+            keep_actions: components.keep_actions,
             ..Default::default()
         };
 
@@ -69,6 +117,7 @@ impl OrgComponents {
                 component: None,
                 obsolete: package.obsolete,
                 renamed: package.renamed,
+                renamed_to: package.renamed_to.clone(),
                 runtime_dependents: package.runtime_dependents.clone(),
                 build_dependents: Vec::new(),
                 test_dependents: Vec::new(),
@@ -96,6 +145,8 @@ impl OrgComponents {
                 test: c(component.test),
                 sys_build: c(component.sys_build),
                 sys_test: c(component.sys_test),
+                license: component.license,
+                license_file: component.license_file,
             });
 
             org_components.components.push(clone!(&a));
@@ -124,14 +175,16 @@ impl OrgComponents {
             package.sys_test_dependents = c(p.sys_test_dependents.clone());
         }
 
-        Ok(org_components)
+        org_components
     }
 
-    pub fn serialize<P: AsRef<Path> + ?Sized + Display>(&self, path: &P) -> Result<(), String> {
+    fn to_serializable(&self) -> Components {
         let mut components = Components {
             packages: Vec::new(),
             components: Vec::new(),
             problems: self.problems.clone(),
+            keep_history: self.keep_history,
+            keep_actions: self.keep_actions,
         };
 
         let cn = |c: shared_type!(OrgComponent)| -> String { get!(c).get_name().clone() };
@@ -146,6 +199,7 @@ impl OrgComponents {
                 component: package.component.clone().map(cn),
                 obsolete: package.is_obsolete(),
                 renamed: package.is_renamed(),
+                renamed_to: package.renamed_to.clone(),
                 runtime_dependents: package.runtime_dependents.clone(),
                 build_dependents: package.build_dependents.iter().map(cnr).collect(),
                 test_dependents: package.test_dependents.iter().map(cnr).collect(),
@@ -163,16 +217,11 @@ impl OrgComponents {
                 test: component.test.iter().map(f).collect(),
                 sys_build: component.sys_build.iter().map(f).collect(),
                 sys_test: component.sys_test.iter().map(f).collect(),
+                license: component.license.clone(),
+                license_file: component.license_file.clone(),
             });
         }
 
-        File::create(path)
-            .map_err(|e| format!("failed to create file {}: {}", path, e))?
-            .write_all(
-                &serialize(&components)
-                    .map_err(|e| format!("failed to serialize components: {}", e))?,
-            )
-            .map_err(|e| format!("failed to write serialized data into file: {}", e))?;
-        Ok(())
+        components
     }
 }
@@ -0,0 +1,328 @@
+use std::{collections::BTreeMap, slice};
+
+use fmri::FMRI;
+
+use crate::{
+    assets::manifest::Action,
+    get,
+    problems::{
+        Problem,
+        Problem::{DanglingLink, LinkTargetNotInDependencies, PathConflict},
+    },
+    Components,
+};
+
+/// actions which deliver something into path
+const PATH_ACTIONS: [&str; 4] = ["file", "dir", "link", "hardlink"];
+
+/// package, its component (empty if package is not in component) and action delivering path
+type Delivery = (FMRI, String, Action);
+
+/// how many links can be followed when target is resolved
+const MAX_LINKS: usize = 16;
+
+impl Components {
+    /// Returns actions delivering paths (`file`, `dir`, `link` and `hardlink`) of non-obsolete and
+    /// non-renamed packages, indexed by path (without leading `/`)
+    ///
+    /// Actions are known only if manifests are loaded (see [`Components::set_keep_actions`]).
+    pub fn get_path_index(&self) -> BTreeMap<String, Vec<Delivery>> {
+        let mut index: BTreeMap<String, Vec<Delivery>> = BTreeMap::new();
+
+        for p in &self.packages {
+            let package = get!(p);
+            if package.is_obsolete() || package.is_renamed() {
+                continue;
+            }
+
+            let component_name = match package.is_in_component() {
+                Some(c) => get!(c).get_name().clone(),
+                None => "".to_owned(),
+            };
+
+            let actions = match package.get_effective_version() {
+                Some(version) => version.get_actions(),
+                None => continue,
+            };
+
+            for action in actions {
+                if !PATH_ACTIONS.contains(&action.name.as_str()) {
+                    continue;
+                }
+
+                if let Some(path) = action.get("path") {
+                    index
+                        .entry(path.trim_start_matches('/').to_owned())
+                        .or_default()
+                        .push((package.fmri.clone(), component_name.clone(), action.clone()));
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Adds [`PathConflict`] for every path delivered by more non-obsolete packages in a way pkg
+    /// can not install (different action types, files, directories or links with different
+    /// attributes)
+    ///
+    /// Links with different mediators, overlaid files and actions for different variants (e.g.
+    /// `variant.arch`) do not conflict.
+    pub fn check_path_conflicts(&mut self) -> Result<(), String> {
+        let mut problems: Vec<Problem> = Vec::new();
+
+        for (path, deliveries) in self.get_path_index() {
+            let mut conflicting: Vec<(FMRI, String, String)> = Vec::new();
+
+            for (i, (fmri_a, component_a, a)) in deliveries.iter().enumerate() {
+                for (fmri_b, component_b, b) in &deliveries[i + 1..] {
+                    if fmri_a.package_name_eq(fmri_b)
+                        || are_variant_disjoint(a, b)
+                        || !conflicts(a, b)
+                    {
+                        continue;
+                    }
+
+                    for (fmri, component, action) in
+                        [(fmri_a, component_a, a), (fmri_b, component_b, b)]
+                    {
+                        let delivery = (fmri.clone(), component.clone(), action.name.clone());
+                        if !conflicting.contains(&delivery) {
+                            conflicting.push(delivery);
+                        }
+                    }
+                }
+            }
+
+            if !conflicting.is_empty() {
+                conflicting.sort_by(|(a, _, _), (b, _, _)| {
+                    a.get_package_name_as_ref_string()
+                        .cmp(b.get_package_name_as_ref_string())
+                });
+                problems.push(PathConflict(path, conflicting));
+            }
+        }
+
+        for problem in problems {
+            self.problems.add_problem(problem);
+        }
+
+        Ok(())
+    }
+
+    /// Adds [`DanglingLink`] for every `link` and `hardlink` of non-obsolete package whose target
+    /// is not delivered by any package and [`LinkTargetNotInDependencies`] if target is delivered
+    /// only by packages which are not in install closure of the package (missing runtime
+    /// dependency)
+    ///
+    /// Targets are reported only if all non-obsolete packages of install closure have actions,
+    /// otherwise the target may be delivered by package whose manifest is not loaded (e.g. package
+    /// which is not in oi-userland).
+    pub fn check_dangling_links(&mut self) -> Result<(), String> {
+        let index = self.get_path_index();
+        let mut problems: Vec<Problem> = Vec::new();
+        // install closures of packages and whether all their packages have actions, by package
+        // name
+        let mut closures: BTreeMap<String, (Vec<FMRI>, bool)> = BTreeMap::new();
+
+        for (path, deliveries) in &index {
+            for (fmri, component_name, action) in deliveries {
+                if action.name != "link" && action.name != "hardlink" {
+                    continue;
+                }
+
+                let target = match action.get("target") {
+                    Some(target) => target,
+                    None => continue,
+                };
+
+                let (closure, complete) = match closures.get(fmri.get_package_name_as_ref_string())
+                {
+                    Some(closure) => closure,
+                    None => {
+                        let closure = self.get_install_closure(slice::from_ref(fmri))?.packages;
+                        let complete = self.have_actions(&closure);
+                        closures
+                            .entry(fmri.get_package_name_as_ref_string().clone())
+                            .or_insert((closure, complete))
+                    }
+                };
+
+                // packages delivering target for the same variant as link
+                let providers = resolve_link(&index, path, target, action)
+                    .and_then(|target| index.get(&target))
+                    .map(|providers| {
+                        providers
+                            .iter()
+                            .filter(|(_, _, a)| !are_variant_disjoint(action, a))
+                            .map(|(f, _, _)| f.clone())
+                            .collect::<Vec<FMRI>>()
+                    })
+                    .unwrap_or_default();
+
+                if providers
+                    .iter()
+                    .any(|f| closure.iter().any(|c| c.package_name_eq(f)))
+                {
+                    continue;
+                }
+
+                // target may be delivered by package whose actions are not known
+                if !complete {
+                    continue;
+                }
+
+                if providers.is_empty() {
+                    problems.push(DanglingLink(
+                        fmri.clone(),
+                        path.clone(),
+                        target.clone(),
+                        component_name.clone(),
+                    ));
+                } else {
+                    problems.push(LinkTargetNotInDependencies(
+                        fmri.clone(),
+                        path.clone(),
+                        target.clone(),
+                        providers,
+                        component_name.clone(),
+                    ));
+                }
+            }
+        }
+
+        for problem in problems {
+            self.problems.add_problem(problem);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if all non-obsolete and non-renamed packages have actions
+    fn have_actions(&self, fmris: &[FMRI]) -> bool {
+        fmris
+            .iter()
+            .all(|fmri| match self.get_package_by_fmri(fmri) {
+                Ok(p) => {
+                    let package = get!(p);
+                    package.is_obsolete()
+                        || package.is_renamed()
+                        || package
+                            .get_effective_version()
+                            .is_some_and(|v| !v.get_actions().is_empty())
+                }
+                Err(_) => false,
+            })
+    }
+}
+
+/// Returns path (without leading `/`) which link in `path` points to, links in directories of
+/// target are followed, returns None if there are too many links
+///
+/// Only links in directories which can be installed together with `link` (not for other variant)
+/// are followed, link with the same mediation as `link` is preferred.
+fn resolve_link(
+    index: &BTreeMap<String, Vec<Delivery>>,
+    path: &str,
+    target: &str,
+    link: &Action,
+) -> Option<String> {
+    let mediation = |a: &Action| {
+        ["mediator", "mediator-version", "mediator-implementation"].map(|k| a.get(k).cloned())
+    };
+
+    let mut resolved: Vec<String> = Vec::new();
+    let mut remaining: Vec<String> = Vec::new();
+    let mut links = 0;
+
+    let push_target = |resolved: &mut Vec<String>, remaining: &mut Vec<String>, target: &str| {
+        if target.starts_with('/') {
+            resolved.clear();
+        }
+        for part in target.split('/').rev() {
+            remaining.push(part.to_owned());
+        }
+    };
+
+    // target is relative to directory of link
+    resolved.extend(path.split('/').map(|p| p.to_owned()));
+    resolved.pop();
+    push_target(&mut resolved, &mut remaining, target);
+
+    while let Some(part) = remaining.pop() {
+        match part.as_str() {
+            "" | "." => continue,
+            ".." => {
+                resolved.pop();
+                continue;
+            }
+            _ => resolved.push(part),
+        }
+
+        // follow link in directory of target
+        if remaining.iter().all(|p| p.is_empty() || p == ".") {
+            continue;
+        }
+        let link_target = index.get(&resolved.join("/")).and_then(|deliveries| {
+            let links = deliveries
+                .iter()
+                .map(|(_, _, a)| a)
+                .filter(|a| a.name == "link" && !are_variant_disjoint(link, a))
+                .collect::<Vec<&Action>>();
+
+            links
+                .iter()
+                .find(|a| mediation(a) == mediation(link))
+                .or(links.first())
+                .and_then(|a| a.get("target"))
+        });
+        if let Some(link_target) = link_target {
+            links += 1;
+            if links > MAX_LINKS {
+                return None;
+            }
+            resolved.pop();
+            push_target(&mut resolved, &mut remaining, link_target);
+        }
+    }
+
+    Some(resolved.join("/"))
+}
+
+/// Returns true if actions can not be installed into the same path
+fn conflicts(a: &Action, b: &Action) -> bool {
+    if a.name != b.name {
+        return true;
+    }
+
+    let differ = |attributes: &[&str]| attributes.iter().any(|k| a.get(k) != b.get(k));
+
+    match a.name.as_str() {
+        "dir" => differ(&["owner", "group", "mode"]),
+        "link" | "hardlink" => match (a.get("mediator"), b.get("mediator")) {
+            // mediation selects one of the links
+            (Some(mediator_a), Some(mediator_b)) => mediator_a != mediator_b,
+            (None, None) => differ(&["target"]),
+            // mediated link can not share path with link which is not mediated
+            _ => true,
+        },
+        "file" => {
+            // only file which allows overlay can be overlaid by other file
+            let overlay = |a: &Action, b: &Action| {
+                a.get("overlay").is_some_and(|o| o == "allow")
+                    && b.get("overlay").is_some_and(|o| o == "true")
+            };
+            !overlay(a, b) && !overlay(b, a)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if actions have the same variant with different values, so they are never
+/// installed together
+pub(crate) fn are_variant_disjoint(a: &Action, b: &Action) -> bool {
+    a.attributes
+        .iter()
+        .filter(|(k, _)| k.starts_with("variant."))
+        .any(|(k, v)| b.get(k).is_some_and(|value| value != v))
+}
@@ -0,0 +1,431 @@
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+use fmri::{Version, FMRI};
+use log::warn;
+
+use crate::{
+    assets::open_indiana_oi_userland_git::component_path,
+    get,
+    packages::{
+        dependency_type::{
+            DependencyTypes,
+            DependencyTypes::{Build, Runtime, SystemBuild, SystemTest, Test},
+        },
+        package::Package,
+    },
+    problems::Problem::{ObsoletedRequired, RenamedNeedsRenamed},
+    weak_type, Components, DependTypes,
+};
+
+/// Planned change of component Makefile or history file
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fix {
+    /// removes obsolete package from `*REQUIRED_PACKAGES` of component
+    RemoveDependency {
+        component: String,
+        dependency_type: DependencyTypes,
+        fmri: FMRI,
+    },
+    /// replaces renamed package in `*REQUIRED_PACKAGES` of component with its successor
+    ReplaceDependency {
+        component: String,
+        dependency_type: DependencyTypes,
+        fmri: FMRI,
+        replacement: FMRI,
+    },
+    /// replaces rename target (which is renamed too) in history file with its successor
+    ReplaceRenameTarget {
+        fmri: FMRI,
+        renamed_to: FMRI,
+        replacement: FMRI,
+    },
+}
+
+/// Changed file with unified diff
+#[derive(Clone, Debug)]
+pub struct FilePatch {
+    pub path: PathBuf,
+    /// path relative to oi-userland repository (used in diff header)
+    pub label: String,
+    original: Vec<String>,
+    /// new content of lines (`None` means removed line)
+    changes: BTreeMap<usize, Option<String>>,
+}
+
+impl Components {
+    /// Returns fixes for [`ObsoletedRequired`] and [`RenamedNeedsRenamed`] problems
+    pub fn get_fixes(&self) -> Vec<Fix> {
+        let mut fixes: Vec<Fix> = Vec::new();
+
+        let mut add = |fix: Fix| {
+            if !fixes.contains(&fix) {
+                fixes.push(fix)
+            }
+        };
+
+        for problem in self.problems.get_ref() {
+            match problem {
+                ObsoletedRequired(DependTypes::Require(fmri), dependency_type, _, component)
+                    if dependency_type != &Runtime =>
+                {
+                    add(Fix::RemoveDependency {
+                        component: component.clone(),
+                        dependency_type: dependency_type.clone(),
+                        fmri: fmri.clone(),
+                    })
+                }
+                RenamedNeedsRenamed(fmri_a, fmri_b) => {
+                    let replacement = match self.resolve_rename(fmri_b) {
+                        Some(r) => r,
+                        None => {
+                            warn!("can not resolve rename of {}, skipping", fmri_b);
+                            continue;
+                        }
+                    };
+
+                    let package_a = match self.get_package_by_fmri(fmri_a) {
+                        Ok(p) => get!(p),
+                        Err(e) => {
+                            warn!("{}, skipping", e);
+                            continue;
+                        }
+                    };
+
+                    if let Some(c) = package_a.is_in_component() {
+                        let component = get!(c);
+
+                        let contains = |deps: &Vec<weak_type!(Package)>| -> bool {
+                            deps.iter()
+                                .any(|p| get!(p.upgrade().unwrap()).fmri.package_name_eq(fmri_b))
+                        };
+
+                        for (deps, dependency_type) in [
+                            (&component.build, Build),
+                            (&component.test, Test),
+                            (&component.sys_build, SystemBuild),
+                            (&component.sys_test, SystemTest),
+                        ] {
+                            if contains(deps) {
+                                add(Fix::ReplaceDependency {
+                                    component: component.get_name().clone(),
+                                    dependency_type,
+                                    fmri: fmri_b.clone(),
+                                    replacement: replacement.clone(),
+                                })
+                            }
+                        }
+                    }
+
+                    if package_a
+                        .get_renamed_to()
+                        .as_ref()
+                        .is_some_and(|r| r.package_name_eq(fmri_b))
+                    {
+                        add(Fix::ReplaceRenameTarget {
+                            fmri: fmri_a.clone(),
+                            renamed_to: fmri_b.clone(),
+                            replacement,
+                        })
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        fixes
+    }
+
+    /// Creates patches of files in oi-userland for `fixes`
+    pub fn create_patches(
+        &self,
+        fixes: &[Fix],
+        oi_userland_components: &Path,
+    ) -> Result<Vec<FilePatch>, String> {
+        let mut patches: BTreeMap<PathBuf, FilePatch> = BTreeMap::new();
+
+        for fix in fixes {
+            match fix {
+                Fix::RemoveDependency {
+                    component,
+                    dependency_type,
+                    fmri,
+                } => {
+                    let path = component_path(oi_userland_components, component).join("Makefile");
+                    match open_patch(&mut patches, &path, oi_userland_components)? {
+                        Some(patch) => patch.remove_dependency(dependency_type, fmri),
+                        None => warn!("{} does not exist", path.display()),
+                    }
+                }
+                Fix::ReplaceDependency {
+                    component,
+                    dependency_type,
+                    fmri,
+                    replacement,
+                } => {
+                    let obsolete = get!(self.get_package_by_fmri(replacement)?).is_obsolete();
+
+                    let path = component_path(oi_userland_components, component).join("Makefile");
+                    match open_patch(&mut patches, &path, oi_userland_components)? {
+                        Some(patch) if obsolete => patch.remove_dependency(dependency_type, fmri),
+                        Some(patch) => patch.replace_dependency(dependency_type, fmri, replacement),
+                        None => warn!("{} does not exist", path.display()),
+                    }
+                }
+                Fix::ReplaceRenameTarget {
+                    fmri,
+                    renamed_to,
+                    replacement,
+                } => {
+                    let version = get!(self.get_package_by_fmri(replacement)?)
+                        .get_effective_version()
+                        .map(|v| v.get_version().clone());
+
+                    for c in self.get_components() {
+                        let path = component_path(oi_userland_components, get!(c).get_name())
+                            .join("history");
+                        if let Some(patch) =
+                            open_patch(&mut patches, &path, oi_userland_components)?
+                        {
+                            patch.replace_rename_target(fmri, renamed_to, replacement, &version)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(patches
+            .into_values()
+            .filter(|p| !p.changes.is_empty())
+            .collect())
+    }
+}
+
+impl FilePatch {
+    fn open(path: &Path, oi_userland_components: &Path) -> Result<Self, String> {
+        let content = read_to_string(path)
+            .map_err(|e| format!("failed to read file {}: {}", path.display(), e))?;
+
+        let label = match oi_userland_components.parent() {
+            Some(root) => path.strip_prefix(root).unwrap_or(path),
+            None => path,
+        };
+
+        Ok(Self {
+            path: path.to_owned(),
+            label: label.display().to_string(),
+            original: content.lines().map(|l| l.to_owned()).collect(),
+            changes: BTreeMap::new(),
+        })
+    }
+
+    /// Returns current content of lines with index
+    fn lines(&self) -> impl Iterator<Item = (usize, &String)> {
+        self.original
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| match self.changes.get(&i) {
+                None => Some((i, line)),
+                Some(changed) => changed.as_ref().map(|c| (i, c)),
+            })
+    }
+
+    /// Returns indexes of lines with `VARIABLE += fmri`
+    fn find_dependency(&self, dependency_type: &DependencyTypes, fmri: &FMRI) -> Vec<usize> {
+        let variable = makefile_variable(dependency_type);
+
+        self.lines()
+            .filter(|(_, line)| match line.split_once("+=") {
+                Some((v, value)) if v.trim() == variable => {
+                    FMRI::parse_raw(value.trim()).is_ok_and(|f| f.package_name_eq(fmri))
+                }
+                _ => false,
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn remove_dependency(&mut self, dependency_type: &DependencyTypes, fmri: &FMRI) {
+        for i in self.find_dependency(dependency_type, fmri) {
+            self.changes.insert(i, None);
+        }
+    }
+
+    fn replace_dependency(
+        &mut self,
+        dependency_type: &DependencyTypes,
+        fmri: &FMRI,
+        replacement: &FMRI,
+    ) {
+        // successor is already there, renamed package is just removed
+        if !self
+            .find_dependency(dependency_type, replacement)
+            .is_empty()
+        {
+            return self.remove_dependency(dependency_type, fmri);
+        }
+
+        let indexes = self.find_dependency(dependency_type, fmri);
+        let new_lines = self
+            .lines()
+            .filter(|(i, _)| indexes.contains(i))
+            .map(|(i, line)| {
+                let (variable, value) = line.split_once("+=").unwrap();
+                let value = value.replacen(
+                    value.trim(),
+                    replacement.get_package_name_as_ref_string(),
+                    1,
+                );
+                (i, format!("{}+={}", variable, value))
+            })
+            .collect::<Vec<(usize, String)>>();
+
+        for (i, line) in new_lines {
+            self.changes.insert(i, Some(line));
+        }
+    }
+
+    fn replace_rename_target(
+        &mut self,
+        fmri: &FMRI,
+        renamed_to: &FMRI,
+        replacement: &FMRI,
+        version: &Option<Version>,
+    ) -> Result<(), String> {
+        let mut new_lines: Vec<(usize, String)> = Vec::new();
+
+        for (i, line) in self.lines() {
+            let columns = line.split_whitespace().collect::<Vec<&str>>();
+            if columns.len() < 2 {
+                continue;
+            }
+
+            let (old, target) = match (FMRI::parse_raw(columns[0]), FMRI::parse_raw(columns[1])) {
+                (Ok(old), Ok(target)) => (old, target),
+                _ => continue,
+            };
+
+            if !old.package_name_eq(fmri) || !target.package_name_eq(renamed_to) {
+                continue;
+            }
+
+            let mut new_target = replacement.clone();
+            new_target.remove_version();
+            if let (true, Some(v)) = (target.has_version(), version) {
+                new_target.change_version(Version::new(
+                    v.to_string().split(':').next().unwrap().to_owned(),
+                )?);
+            }
+
+            let mut new_target = new_target.to_string();
+            if !columns[1].starts_with("pkg:/") {
+                new_target = new_target.trim_start_matches("pkg:/").to_owned();
+            }
+
+            new_lines.push((i, line.replacen(columns[1], &new_target, 1)));
+        }
+
+        for (i, line) in new_lines {
+            self.changes.insert(i, Some(line));
+        }
+        Ok(())
+    }
+
+    /// Returns unified diff of changes
+    pub fn diff(&self) -> String {
+        const CONTEXT: usize = 3;
+
+        let mut hunks: Vec<(usize, usize)> = Vec::new();
+        for &i in self.changes.keys() {
+            let start = i.saturating_sub(CONTEXT);
+            let end = (i + CONTEXT + 1).min(self.original.len());
+            match hunks.last_mut() {
+                Some((_, last_end)) if start <= *last_end => *last_end = end,
+                _ => hunks.push((start, end)),
+            }
+        }
+
+        let mut diff = format!("--- a/{}\n+++ b/{}\n", self.label, self.label);
+        let mut offset: isize = 0;
+
+        for (start, end) in hunks {
+            let mut body = String::new();
+            let mut new_len = 0;
+
+            for i in start..end {
+                match self.changes.get(&i) {
+                    None => {
+                        body.push_str(&format!(" {}\n", self.original[i]));
+                        new_len += 1;
+                    }
+                    Some(changed) => {
+                        body.push_str(&format!("-{}\n", self.original[i]));
+                        if let Some(line) = changed {
+                            body.push_str(&format!("+{}\n", line));
+                            new_len += 1;
+                        }
+                    }
+                }
+            }
+
+            let old_len = end - start;
+            // empty range starts at the line before
+            let new_start = start as isize + offset + if new_len == 0 { 0 } else { 1 };
+            diff.push_str(&format!(
+                "@@ -{},{} +{},{} @@\n",
+                start + 1,
+                old_len,
+                new_start,
+                new_len
+            ));
+            diff.push_str(&body);
+
+            offset += new_len as isize - old_len as isize;
+        }
+
+        diff
+    }
+
+    /// Writes changes into file
+    pub fn apply(&self) -> Result<(), String> {
+        let mut content = self
+            .lines()
+            .map(|(_, l)| l.clone())
+            .collect::<Vec<String>>()
+            .join("\n");
+        content.push('\n');
+
+        write(&self.path, content)
+            .map_err(|e| format!("failed to write file {}: {}", self.path.display(), e))
+    }
+}
+
+fn open_patch<'a>(
+    patches: &'a mut BTreeMap<PathBuf, FilePatch>,
+    path: &Path,
+    oi_userland_components: &Path,
+) -> Result<Option<&'a mut FilePatch>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    if !patches.contains_key(path) {
+        let patch = FilePatch::open(path, oi_userland_components)?;
+        patches.insert(path.to_owned(), patch);
+    }
+
+    Ok(patches.get_mut(path))
+}
+
+pub(crate) fn makefile_variable(dependency_type: &DependencyTypes) -> &'static str {
+    match dependency_type {
+        Runtime => "",
+        Build => "REQUIRED_PACKAGES",
+        Test => "TEST_REQUIRED_PACKAGES",
+        SystemBuild => "USERLAND_REQUIRED_PACKAGES",
+        SystemTest => "USERLAND_TEST_REQUIRED_PACKAGES",
+    }
+}
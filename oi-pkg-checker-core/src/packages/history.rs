@@ -0,0 +1,131 @@
+use fmri::{FMRIList, Version, FMRI};
+
+use crate::packages::{
+    depend_types::DependTypes,
+    package::{Package, PackageVersion},
+};
+
+/// Change of package state between two versions
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transition {
+    Obsoleted,
+    Renamed,
+    /// package was obsolete or renamed and now it is normal again
+    Revived,
+}
+
+/// One version in the timeline of package
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub version: Version,
+    /// timestamp from version (e.g. `20171212T185746Z`)
+    pub timestamp: Option<String>,
+    pub obsolete: bool,
+    pub renamed: bool,
+    /// change of state compared to previous version
+    pub transition: Option<Transition>,
+    /// runtime dependencies added since previous version
+    pub added: Vec<DependTypes>,
+    /// runtime dependencies removed since previous version
+    pub removed: Vec<DependTypes>,
+}
+
+impl Package {
+    /// Returns timeline of package versions from the oldest to the newest.
+    ///
+    /// Dependencies are compared without versions, so raising a minimal version of dependency
+    /// is not reported as change.
+    pub fn get_history(&self) -> Vec<HistoryEntry> {
+        let mut versions = self.versions.iter().collect::<Vec<&PackageVersion>>();
+        versions.sort_by(|a, b| a.version.cmp(&b.version));
+
+        let mut history: Vec<HistoryEntry> = Vec::new();
+        let mut previous: Option<&PackageVersion> = None;
+
+        for version in versions {
+            let dependencies = without_versions(version.get_runtime_dependencies());
+
+            let (added, removed) = match previous {
+                None => (dependencies, Vec::new()),
+                Some(p) => {
+                    let old = without_versions(p.get_runtime_dependencies());
+                    (
+                        dependencies
+                            .iter()
+                            .filter(|d| !old.contains(d))
+                            .cloned()
+                            .collect(),
+                        old.iter()
+                            .filter(|d| !dependencies.contains(d))
+                            .cloned()
+                            .collect(),
+                    )
+                }
+            };
+
+            history.push(HistoryEntry {
+                version: version.version.clone(),
+                timestamp: version.get_timestamp(),
+                obsolete: version.is_obsolete(),
+                renamed: version.is_renamed(),
+                transition: state_transition(previous, version),
+                added,
+                removed,
+            });
+
+            previous = Some(version);
+        }
+
+        history
+    }
+}
+
+fn state_transition(
+    previous: Option<&PackageVersion>,
+    version: &PackageVersion,
+) -> Option<Transition> {
+    let (obsolete, renamed) =
+        previous.map_or((false, false), |p| (p.is_obsolete(), p.is_renamed()));
+
+    if version.is_obsolete() && !obsolete {
+        Some(Transition::Obsoleted)
+    } else if version.is_renamed() && !renamed {
+        Some(Transition::Renamed)
+    } else if !version.is_obsolete() && !version.is_renamed() && (obsolete || renamed) {
+        Some(Transition::Revived)
+    } else {
+        None
+    }
+}
+
+fn without_versions(dependencies: &[DependTypes]) -> Vec<DependTypes> {
+    let f = |fmri: &FMRI| -> FMRI {
+        let mut fmri = fmri.clone();
+        fmri.remove_version();
+        fmri
+    };
+
+    let mut dependencies = dependencies
+        .iter()
+        .map(|d| match d {
+            DependTypes::Require(a) => DependTypes::Require(f(a)),
+            DependTypes::Optional(a) => DependTypes::Optional(f(a)),
+            DependTypes::Exclude(a) => DependTypes::Exclude(f(a)),
+            DependTypes::Incorporate(a) => DependTypes::Incorporate(f(a)),
+            DependTypes::RequireAny(l) => DependTypes::RequireAny(FMRIList::from(
+                l.get_ref().iter().map(f).collect::<Vec<FMRI>>(),
+            )),
+            DependTypes::GroupAny(l) => DependTypes::GroupAny(FMRIList::from(
+                l.get_ref().iter().map(f).collect::<Vec<FMRI>>(),
+            )),
+            DependTypes::Conditional(a, p) => DependTypes::Conditional(f(a), f(p)),
+            DependTypes::Origin(a) => DependTypes::Origin(f(a)),
+            DependTypes::Group(a) => DependTypes::Group(f(a)),
+            DependTypes::Parent(a) => DependTypes::Parent(f(a)),
+        })
+        .collect::<Vec<DependTypes>>();
+
+    dependencies.sort();
+    dependencies.dedup();
+    dependencies
+}
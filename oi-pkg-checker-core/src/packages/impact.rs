@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use fmri::FMRI;
+
+use crate::{
+    get,
+    packages::{
+        checks::CheckOptions,
+        components::Components,
+        dependency_type::{
+            DependencyTypes,
+            DependencyTypes::{Build, SystemBuild, SystemTest, Test},
+        },
+        rev_depend_type::RevDependType::*,
+    },
+    problems::Problem,
+};
+
+/// How strongly is package affected
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImpactKind {
+    /// reached only through require dependencies
+    Require,
+    /// reached through optional, conditional, require-any or group dependency
+    Soft,
+    /// reached through build or test dependency of component
+    Build,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImpactedComponent {
+    pub name: String,
+    /// affected packages in component
+    pub packages: Vec<(FMRI, ImpactKind)>,
+    /// types of dependencies of component on affected packages
+    pub dependency_types: Vec<DependencyTypes>,
+}
+
+/// Result of impact analysis
+#[derive(Clone, Debug)]
+pub struct Impact {
+    pub components: Vec<ImpactedComponent>,
+    /// affected packages which are not in any component
+    pub packages: Vec<(FMRI, ImpactKind)>,
+    /// problems which would appear if packages were obsoleted
+    pub problems: Vec<Problem>,
+}
+
+impl Components {
+    /// Finds everything that depends (transitively) on `fmris` and problems that would
+    /// appear if `fmris` were obsoleted.
+    pub fn get_impact(&self, fmris: &[FMRI], options: &CheckOptions) -> Result<Impact, String> {
+        let mut inputs: Vec<FMRI> = Vec::new();
+        for fmri in fmris {
+            inputs.push(get!(self.get_package_by_fmri(fmri)?).fmri.clone());
+        }
+
+        let mut packages: HashMap<FMRI, ImpactKind> = HashMap::new();
+        let mut components: BTreeMap<String, Vec<DependencyTypes>> = BTreeMap::new();
+        let mut queue: VecDeque<(FMRI, Option<ImpactKind>)> =
+            inputs.iter().map(|f| (f.clone(), None)).collect();
+
+        while let Some((fmri, kind)) = queue.pop_front() {
+            let package = get!(self.get_package_by_fmri(&fmri)?);
+
+            let mut visit = |f: &FMRI, edge: ImpactKind| {
+                let kind = kind.clone().map_or(edge.clone(), |k| k.max(edge));
+
+                if inputs.contains(f) || packages.get(f).is_some_and(|k| k <= &kind) {
+                    return;
+                }
+
+                packages.insert(f.clone(), kind.clone());
+                queue.push_back((f.clone(), Some(kind)));
+            };
+
+            for rev_dep in &package.runtime_dependents {
+                let (f, edge) = match rev_dep {
+                    Require(f) => (f, ImpactKind::Require),
+                    Optional(f)
+                    | RequireAny(f)
+                    | ConditionalFmri(f)
+                    | ConditionalPredicate(f)
+                    | Group(f) => (f, ImpactKind::Soft),
+                    Incorporate(_) => continue,
+                };
+
+                if get!(self.get_package_by_fmri(f)?).is_obsolete() {
+                    continue;
+                }
+
+                visit(f, edge);
+            }
+
+            for (dependents, dependency_type) in [
+                (&package.build_dependents, Build),
+                (&package.test_dependents, Test),
+                (&package.sys_build_dependents, SystemBuild),
+                (&package.sys_test_dependents, SystemTest),
+            ] {
+                for c in dependents {
+                    let component = get!(c);
+
+                    let types = components.entry(component.get_name().clone()).or_default();
+                    if !types.contains(&dependency_type) {
+                        types.push(dependency_type.clone());
+                    }
+
+                    for p in &component.packages {
+                        visit(&get!(p.upgrade().unwrap()).fmri, ImpactKind::Build);
+                    }
+                }
+            }
+        }
+
+        let mut impact = Impact {
+            components: Vec::new(),
+            packages: Vec::new(),
+            problems: self.get_obsoletion_problems(&inputs, options)?,
+        };
+
+        let mut by_component: BTreeMap<String, Vec<(FMRI, ImpactKind)>> = BTreeMap::new();
+        for (fmri, kind) in packages {
+            match get!(self.get_package_by_fmri(&fmri)?).is_in_component() {
+                Some(c) => by_component
+                    .entry(get!(c).get_name().clone())
+                    .or_default()
+                    .push((fmri, kind)),
+                None => impact.packages.push((fmri, kind)),
+            }
+        }
+
+        for name in by_component.keys() {
+            components.entry(name.clone()).or_default();
+        }
+
+        for (name, mut dependency_types) in components {
+            let mut packages = by_component.remove(&name).unwrap_or_default();
+            packages.sort();
+            dependency_types.sort();
+
+            impact.components.push(ImpactedComponent {
+                name,
+                packages,
+                dependency_types,
+            });
+        }
+
+        impact.packages.sort();
+
+        Ok(impact)
+    }
+
+    /// Returns problems which [`Components::check_all`] would find if `fmris` were obsoleted
+    pub fn get_obsoletion_problems(
+        &self,
+        fmris: &[FMRI],
+        options: &CheckOptions,
+    ) -> Result<Vec<Problem>, String> {
+        let mut components = self.deep_clone();
+        components.check_all(options)?;
+        let before = components.problems.get_ref().len();
+
+        for fmri in fmris {
+            components.obsolete_whole_package(fmri)?;
+        }
+
+        components.check_all(options)?;
+
+        Ok(components.problems.get_ref()[before..].to_vec())
+    }
+}
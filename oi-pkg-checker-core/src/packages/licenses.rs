@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use crate::{
+    assets::manifest::Action,
+    get,
+    problems::{
+        Problem,
+        Problem::{LicenseMismatch, MissingLicense},
+    },
+    Components,
+};
+
+impl Components {
+    /// Checks `license` actions of non-obsolete and non-renamed packages:
+    ///
+    /// - [`MissingLicense`], package has no `license` action
+    /// - [`LicenseMismatch`], no `license` action of package matches COMPONENT_LICENSE (`license`
+    ///   attribute) or COMPONENT_LICENSE_FILE (license file name) of its component
+    ///
+    /// Packages without actions (manifest is not loaded) are skipped, licenses of components are
+    /// known only if they are loaded from Makefiles.
+    pub fn check_licenses(&mut self) -> Result<(), String> {
+        let mut problems: Vec<Problem> = Vec::new();
+
+        for p in &self.packages {
+            let package = get!(p);
+            if package.is_obsolete() || package.is_renamed() {
+                continue;
+            }
+
+            let licenses = match package.get_effective_version() {
+                Some(version) if !version.get_actions().is_empty() => version
+                    .get_actions()
+                    .iter()
+                    .filter(|a| a.name == "license")
+                    .collect::<Vec<&Action>>(),
+                _ => continue,
+            };
+
+            let (component_name, license, license_file) = match package.is_in_component() {
+                Some(c) => {
+                    let component = get!(c);
+                    (
+                        component.get_name().clone(),
+                        component.get_license().clone(),
+                        component.get_license_file().clone(),
+                    )
+                }
+                None => ("".to_owned(), None, None),
+            };
+
+            if licenses.is_empty() {
+                problems.push(MissingLicense(package.fmri.clone(), component_name));
+                continue;
+            }
+
+            if let Some(license) = license {
+                let found = licenses
+                    .iter()
+                    .filter_map(|a| a.get("license").cloned())
+                    .collect::<Vec<String>>();
+
+                if !found.contains(&license) {
+                    problems.push(LicenseMismatch(
+                        package.fmri.clone(),
+                        component_name.clone(),
+                        "COMPONENT_LICENSE".to_owned(),
+                        license,
+                        found,
+                    ));
+                }
+            }
+
+            if let Some(license_file) = license_file {
+                let found = licenses
+                    .iter()
+                    .filter_map(|a| get_license_file(a))
+                    .collect::<Vec<String>>();
+
+                // file names are not known in published manifests (payload is hash)
+                if !found.is_empty() && !found.contains(&file_name(&license_file)) {
+                    problems.push(LicenseMismatch(
+                        package.fmri.clone(),
+                        component_name,
+                        "COMPONENT_LICENSE_FILE".to_owned(),
+                        license_file,
+                        found,
+                    ));
+                }
+            }
+        }
+
+        for problem in problems {
+            self.problems.add_problem(problem);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns file name of license file of `license` action (`path` attribute or payload which is
+/// not hash)
+fn get_license_file(action: &Action) -> Option<String> {
+    let is_hash =
+        |p: &&String| matches!(p.len(), 40 | 64) && p.chars().all(|c| c.is_ascii_hexdigit());
+
+    action
+        .get("path")
+        .or_else(|| action.payload.first().filter(|p| !is_hash(p)))
+        .map(|path| file_name(path))
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(path.to_owned())
+}
@@ -0,0 +1,205 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use fmri::FMRI;
+
+use crate::{
+    assets::manifest::Action,
+    get,
+    packages::files::are_variant_disjoint,
+    problems::{
+        Problem,
+        Problem::{MediatedPathInOneVersion, MediatorConflict, ObsoleteMediatorDefault},
+    },
+    Components,
+};
+
+/// Mediated link (`link` action with `mediator`)
+#[derive(Clone, Debug)]
+struct MediatedLink {
+    fmri: FMRI,
+    obsolete: bool,
+    path: String,
+    action: Action,
+}
+
+impl MediatedLink {
+    /// Returns `mediator-version` and `mediator-implementation`, e.g. `3.11` or `3.11 (gnu)`
+    fn version(&self) -> String {
+        let version = self.action.get("mediator-version").cloned();
+        let implementation = self.action.get("mediator-implementation");
+
+        match (version, implementation) {
+            (Some(v), Some(i)) => format!("{} ({})", v, i),
+            (Some(v), None) => v,
+            (None, Some(i)) => format!("({})", i),
+            (None, None) => "".to_owned(),
+        }
+    }
+
+    /// Returns rank of `mediator-priority`, `site` is preferred over `vendor` and over links
+    /// without priority
+    fn priority(&self) -> u8 {
+        match self.action.get("mediator-priority").map(|p| p.as_str()) {
+            Some("site") => 2,
+            Some("vendor") => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl Components {
+    /// Checks links mediated by the same mediator:
+    ///
+    /// - [`MediatorConflict`], packages deliver link into the same path for the same mediator
+    ///   version with different targets and the highest `mediator-priority` does not select only
+    ///   one of them
+    /// - [`ObsoleteMediatorDefault`], version selected by default (highest priority, then highest
+    ///   version) is delivered by obsolete package
+    /// - [`MediatedPathInOneVersion`], mediated path is delivered only for one version of mediator
+    ///   which has more versions
+    pub fn check_mediators(&mut self) -> Result<(), String> {
+        let mut problems: Vec<Problem> = Vec::new();
+
+        for (mediator, links) in self.get_mediated_links() {
+            let current = links
+                .iter()
+                .filter(|l| !l.obsolete)
+                .collect::<Vec<&MediatedLink>>();
+
+            // mediated paths and versions of mediator which deliver them
+            let mut paths: BTreeMap<&String, BTreeMap<String, Vec<&MediatedLink>>> =
+                BTreeMap::new();
+            for link in &current {
+                paths
+                    .entry(&link.path)
+                    .or_default()
+                    .entry(link.version())
+                    .or_default()
+                    .push(link);
+            }
+
+            let versions = current.iter().map(|l| l.version()).collect::<Vec<String>>();
+            let has_more_versions = versions.iter().any(|v| v != &versions[0]);
+
+            for (path, versions) in paths {
+                for links in versions.values() {
+                    // only links with the highest priority can be selected
+                    let highest = links.iter().map(|l| l.priority()).max().unwrap_or_default();
+                    let links = links
+                        .iter()
+                        .filter(|l| l.priority() == highest)
+                        .collect::<Vec<&&MediatedLink>>();
+
+                    let mut fmris: Vec<FMRI> = Vec::new();
+                    for (i, a) in links.iter().enumerate() {
+                        for b in &links[i + 1..] {
+                            if a.fmri.package_name_eq(&b.fmri)
+                                || a.action.get("target") == b.action.get("target")
+                                || are_variant_disjoint(&a.action, &b.action)
+                            {
+                                continue;
+                            }
+
+                            for fmri in [&a.fmri, &b.fmri] {
+                                if !fmris.iter().any(|f| f.package_name_eq(fmri)) {
+                                    fmris.push(fmri.clone());
+                                }
+                            }
+                        }
+                    }
+
+                    if !fmris.is_empty() {
+                        problems.push(MediatorConflict(mediator.clone(), path.clone(), fmris));
+                    }
+                }
+
+                if has_more_versions && versions.len() == 1 {
+                    let (version, links) = versions.into_iter().next().unwrap();
+                    problems.push(MediatedPathInOneVersion(
+                        mediator.clone(),
+                        path.clone(),
+                        version,
+                        links[0].fmri.clone(),
+                    ));
+                }
+            }
+
+            if let Some(default) = default_link(&links) {
+                let version = default.version();
+                if !current.iter().any(|l| l.version() == version) {
+                    problems.push(ObsoleteMediatorDefault(
+                        mediator.clone(),
+                        version,
+                        default.fmri.clone(),
+                    ));
+                }
+            }
+        }
+
+        for problem in problems {
+            self.problems.add_problem(problem);
+        }
+
+        Ok(())
+    }
+
+    /// Returns mediated links of packages (including obsolete ones which still have actions),
+    /// grouped by mediator
+    fn get_mediated_links(&self) -> BTreeMap<String, Vec<MediatedLink>> {
+        let mut mediators: BTreeMap<String, Vec<MediatedLink>> = BTreeMap::new();
+
+        for p in &self.packages {
+            let package = get!(p);
+            if package.is_renamed() {
+                continue;
+            }
+
+            let actions = match package.get_effective_version() {
+                Some(version) => version.get_actions(),
+                None => continue,
+            };
+
+            for action in actions.iter().filter(|a| a.name == "link") {
+                if let (Some(mediator), Some(path)) = (action.get("mediator"), action.get("path")) {
+                    mediators
+                        .entry(mediator.clone())
+                        .or_default()
+                        .push(MediatedLink {
+                            fmri: package.fmri.clone(),
+                            obsolete: package.is_obsolete(),
+                            path: path.trim_start_matches('/').to_owned(),
+                            action: action.clone(),
+                        });
+                }
+            }
+        }
+
+        mediators
+    }
+}
+
+/// Returns link selected by default, links with `site` priority are preferred over `vendor` and
+/// over links without priority, then the highest version is selected
+fn default_link(links: &[MediatedLink]) -> Option<&MediatedLink> {
+    links.iter().max_by(|a, b| {
+        a.priority().cmp(&b.priority()).then_with(|| {
+            compare_versions(
+                a.action.get("mediator-version"),
+                b.action.get("mediator-version"),
+            )
+        })
+    })
+}
+
+/// Compares dotted versions (e.g. `3.9` < `3.11`) by numeric parts
+fn compare_versions(a: Option<&String>, b: Option<&String>) -> Ordering {
+    let parts = |v: Option<&String>| {
+        v.map(|v| {
+            v.split('.')
+                .map(|p| p.parse::<u64>().unwrap_or_default())
+                .collect::<Vec<u64>>()
+        })
+    };
+
+    parts(a).cmp(&parts(b))
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use fmri::FMRI;
+
+use crate::{
+    get,
+    packages::{
+        components::Components,
+        dependency_type::DependencyTypes::{Build, SystemBuild, SystemTest, Test},
+        rev_depend_type::RevDependType,
+    },
+    DependTypes,
+};
+
+/// Package which is not needed by anything, with component it is in
+pub type Orphan = (FMRI, Option<String>);
+
+/// Packages which are candidates for obsoletion
+#[derive(Clone, Debug, PartialEq)]
+pub struct Orphans {
+    /// packages outside of `library/`
+    pub applications: Vec<Orphan>,
+    /// packages in `library/`
+    pub libraries: Vec<Orphan>,
+}
+
+impl Components {
+    /// Finds non-obsolete and non-renamed packages which have no runtime dependents (except
+    /// `incorporate`), no build or test dependents and which are not in closure of any `group`
+    /// dependency.
+    ///
+    /// Packages with `group` or `incorporate` dependencies (metapackages and incorporations)
+    /// are roots by design and are not reported.
+    pub fn get_orphans(&self) -> Result<Orphans, String> {
+        let mut group_members: Vec<FMRI> = Vec::new();
+        for p in &self.packages {
+            for d in get!(p)
+                .get_effective_version()
+                .map(|v| v.get_runtime_dependencies().clone())
+                .unwrap_or_default()
+            {
+                if let DependTypes::Group(f) = d {
+                    if self.get_package_by_fmri(&f).is_ok() {
+                        group_members.push(f)
+                    }
+                }
+            }
+        }
+
+        let in_group = self
+            .get_install_closure(&group_members)
+            .map_err(|e| format!("failed to compute closure of group dependencies: {}", e))?
+            .packages
+            .iter()
+            .map(|f| f.get_package_name_as_ref_string().clone())
+            .collect::<HashSet<String>>();
+
+        let mut orphans = Orphans {
+            applications: Vec::new(),
+            libraries: Vec::new(),
+        };
+
+        for p in &self.packages {
+            let package = get!(p);
+            let name = package.fmri.get_package_name_as_ref_string();
+
+            if package.is_obsolete() || package.is_renamed() || in_group.contains(name) {
+                continue;
+            }
+
+            let is_root = package.get_effective_version().is_some_and(|v| {
+                v.get_runtime_dependencies().iter().any(|d| {
+                    matches!(
+                        d,
+                        DependTypes::Group(_)
+                            | DependTypes::GroupAny(_)
+                            | DependTypes::Incorporate(_)
+                    )
+                })
+            });
+
+            let has_runtime_dependent = package.get_runtime_dependents().iter().any(|d| match d {
+                RevDependType::Incorporate(_) => false,
+                RevDependType::Require(f)
+                | RevDependType::Optional(f)
+                | RevDependType::RequireAny(f)
+                | RevDependType::ConditionalFmri(f)
+                | RevDependType::ConditionalPredicate(f)
+                | RevDependType::Group(f) => !f.package_name_eq(&package.fmri),
+            });
+
+            let mut has_git_dependent = false;
+            for dependency_type in [Build, Test, SystemBuild, SystemTest] {
+                has_git_dependent |= !package.get_git_dependents(dependency_type)?.is_empty();
+            }
+
+            if is_root || has_runtime_dependent || has_git_dependent {
+                continue;
+            }
+
+            let component = package
+                .is_in_component()
+                .as_ref()
+                .map(|c| get!(c).get_name().clone());
+            let orphan = (package.fmri.clone(), component);
+
+            match name.starts_with("library/") {
+                true => orphans.libraries.push(orphan),
+                false => orphans.applications.push(orphan),
+            }
+        }
+
+        for list in [&mut orphans.applications, &mut orphans.libraries] {
+            list.sort_by(|(a, _), (b, _)| {
+                a.get_package_name_as_ref_string()
+                    .cmp(b.get_package_name_as_ref_string())
+            });
+        }
+
+        Ok(orphans)
+    }
+}
@@ -1,11 +1,10 @@
 use std::cmp::Ordering;
 
-use fmri::{FMRI, Version};
+use fmri::{Publisher, Version, FMRI};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Component,
-    DependTypes,
+    assets::manifest::Action,
     get,
     packages::{
         dependency_type::{
@@ -13,7 +12,9 @@ use crate::{
             DependencyTypes::{Build, Runtime, SystemBuild, SystemTest, Test},
         },
         rev_depend_type::RevDependType,
-    }, problems::{Problem, Problem::PackageInMultipleComponents}, shared_type,
+    },
+    problems::{Problem, Problem::PackageInMultipleComponents},
+    shared_type, Component, DependTypes,
 };
 
 /// Package. Can hold multiple versions with different runtime dependencies.
@@ -27,6 +28,8 @@ pub struct Package {
     pub(crate) component: Option<shared_type!(Component)>,
     pub(crate) obsolete: bool,
     pub(crate) renamed: bool,
+    /// package this package is renamed to (contains no version)
+    pub(crate) renamed_to: Option<FMRI>,
     /// packages that depend on this package
     pub(crate) runtime_dependents: Vec<RevDependType>,
     pub(crate) build_dependents: Vec<shared_type!(Component)>,
@@ -45,6 +48,7 @@ impl Package {
             runtime_dependents: Vec::new(),
             obsolete: false,
             renamed: false,
+            renamed_to: None,
             build_dependents: Vec::new(),
             test_dependents: Vec::new(),
             sys_build_dependents: Vec::new(),
@@ -72,6 +76,7 @@ impl Package {
 
         self.set_obsolete(package_version.is_obsolete());
         self.set_renamed(package_version.is_renamed());
+        self.set_renamed_to(package_version.get_rename_target());
 
         self.versions.push(package_version);
         Ok(())
@@ -92,6 +97,22 @@ impl Package {
         Ok(())
     }
 
+    pub fn remove_dependent(
+        &mut self,
+        component_name: &str,
+        dependency_type: &DependencyTypes,
+    ) -> Result<(), String> {
+        let dependents = match dependency_type {
+            Runtime => return Err("you can not remove runtime dependent".to_owned()),
+            Build => &mut self.build_dependents,
+            Test => &mut self.test_dependents,
+            SystemBuild => &mut self.sys_build_dependents,
+            SystemTest => &mut self.sys_test_dependents,
+        };
+        dependents.retain(|c| get!(c).get_name() != component_name);
+        Ok(())
+    }
+
     pub fn set_component(&mut self, component: shared_type!(Component)) -> Option<Box<Problem>> {
         if let Some(c) = &self.component {
             return Some(Box::new(PackageInMultipleComponents(
@@ -123,6 +144,13 @@ impl Package {
         self.renamed = renamed
     }
 
+    pub fn set_renamed_to(&mut self, renamed_to: Option<FMRI>) {
+        self.renamed_to = renamed_to.map(|mut f| {
+            f.remove_version();
+            f
+        })
+    }
+
     pub fn is_obsolete(&self) -> bool {
         self.obsolete
     }
@@ -131,6 +159,12 @@ impl Package {
         self.renamed
     }
 
+    /// Returns package this package is renamed to (only direct target, see
+    /// [`Components::get_rename_chain`][crate::Components::get_rename_chain])
+    pub fn get_renamed_to(&self) -> &Option<FMRI> {
+        &self.renamed_to
+    }
+
     pub fn is_in_component(&self) -> &Option<shared_type!(Component)> {
         &self.component
     }
@@ -156,9 +190,59 @@ impl Package {
         self.versions = vers
     }
 
+    /// Returns version used for checks.
+    ///
+    /// It is the newest version which is neither obsolete nor renamed. If there is no such
+    /// version, the newest version is returned.
+    pub fn get_effective_version(&self) -> Option<&PackageVersion> {
+        let newest = |a: &&PackageVersion, b: &&PackageVersion| a.version.cmp(&b.version);
+
+        self.versions
+            .iter()
+            .filter(|v| !v.is_obsolete() && !v.is_renamed())
+            .max_by(newest)
+            .or_else(|| self.versions.iter().max_by(newest))
+    }
+
+    /// Sets obsolete, renamed and rename target of package from its newest version
+    pub(crate) fn set_state_from_newest_version(&mut self) {
+        let (obsolete, renamed, renamed_to) = match self
+            .versions
+            .iter()
+            .max_by(|a, b| a.version.cmp(&b.version))
+        {
+            Some(newest) => (
+                newest.is_obsolete(),
+                newest.is_renamed(),
+                newest.get_rename_target(),
+            ),
+            None => return,
+        };
+
+        self.set_obsolete(obsolete);
+        self.set_renamed(renamed);
+        self.set_renamed_to(renamed_to);
+    }
+
     pub fn get_fmri(&self) -> &FMRI {
         &self.fmri
     }
+
+    /// publisher from catalog (it is removed only from fmris in problems)
+    pub fn get_publisher(&self) -> &Option<Publisher> {
+        self.fmri.get_publisher_ref()
+    }
+
+    /// Returns mutable effective version (see [`Package::get_effective_version`])
+    pub(crate) fn get_effective_version_mut(&mut self) -> Option<&mut PackageVersion> {
+        let version = self.get_effective_version()?.version.clone();
+        self.versions.iter_mut().find(|v| v.version == version)
+    }
+
+    /// Returns metadata of effective version
+    pub fn get_metadata(&self) -> Option<&Metadata> {
+        self.get_effective_version().map(|v| v.get_metadata())
+    }
 }
 
 /// PackageVersion represents one version of package
@@ -170,6 +254,52 @@ pub struct PackageVersion {
     pub(crate) runtime: Vec<DependTypes>,
     obsolete: bool,
     renamed: bool,
+    /// summary, description, ... (from `catalog.summary.C` or manifest)
+    metadata: Metadata,
+    /// all actions from manifest, only if manifests are loaded (see `Components::set_keep_actions`)
+    actions: Vec<Action>,
+}
+
+/// Metadata of package version from `set` actions
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Metadata {
+    /// `pkg.summary`
+    pub summary: Option<String>,
+    /// `pkg.description`
+    pub description: Option<String>,
+    /// `info.classification`, e.g. `org.opensolaris.category.2008:Development/C`
+    pub classification: Vec<String>,
+    /// `org.opensolaris.consolidation`
+    pub consolidation: Option<String>,
+}
+
+impl Metadata {
+    /// names of `set` actions which are stored in metadata
+    pub const NAMES: [&'static str; 5] = [
+        "pkg.summary",
+        "pkg.description",
+        "description",
+        "info.classification",
+        "org.opensolaris.consolidation",
+    ];
+
+    /// Sets value(s) of `set` action, returns false if name is not in [`Metadata::NAMES`]
+    pub fn set(&mut self, name: &str, mut values: Vec<String>) -> bool {
+        match name {
+            "pkg.summary" => self.summary = values.pop(),
+            // `description` is obsolete name of `pkg.summary`
+            "description" => {
+                if self.summary.is_none() {
+                    self.summary = values.pop()
+                }
+            }
+            "pkg.description" => self.description = values.pop(),
+            "info.classification" => self.classification = values,
+            "org.opensolaris.consolidation" => self.consolidation = values.pop(),
+            _ => return false,
+        }
+        true
+    }
 }
 
 impl PackageVersion {
@@ -180,6 +310,8 @@ impl PackageVersion {
             runtime: vec![],
             obsolete: false,
             renamed: false,
+            metadata: Metadata::default(),
+            actions: vec![],
         }
     }
 
@@ -192,6 +324,18 @@ impl PackageVersion {
         &self.runtime
     }
 
+    pub fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Returns timestamp of version (e.g. `20171212T185746Z`), if present
+    pub fn get_timestamp(&self) -> Option<String> {
+        self.version
+            .to_string()
+            .split_once(':')
+            .map(|(_, timestamp)| timestamp.to_owned())
+    }
+
     pub fn set_obsolete(&mut self, obsolete: bool) -> &Self {
         self.obsolete = obsolete;
         self
@@ -209,4 +353,34 @@ impl PackageVersion {
     pub fn is_renamed(&self) -> bool {
         self.renamed
     }
+
+    pub fn set_metadata(&mut self, metadata: Metadata) -> &Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn set_actions(&mut self, actions: Vec<Action>) -> &Self {
+        self.actions = actions;
+        self
+    }
+
+    pub fn get_actions(&self) -> &Vec<Action> {
+        &self.actions
+    }
+
+    /// Returns successor of renamed version (first require dependency)
+    pub fn get_rename_target(&self) -> Option<FMRI> {
+        if !self.renamed {
+            return None;
+        }
+
+        self.runtime.iter().find_map(|d| match d {
+            DependTypes::Require(fmri) => Some(fmri.clone()),
+            _ => None,
+        })
+    }
 }
@@ -0,0 +1,228 @@
+use std::{fs, path::Path};
+
+use fmri::{Publisher, FMRI};
+use serde::Deserialize;
+
+use crate::{
+    get,
+    packages::dependency_type::DependencyTypes::{Build, Runtime, SystemBuild, SystemTest, Test},
+    problems::{Problem, Problem::PublisherPolicyViolation},
+    Components, DependTypes,
+};
+
+/// types which can be used in rules
+const DEPENDENCY_TYPES: [&str; 10] = [
+    "require",
+    "optional",
+    "incorporate",
+    "require-any",
+    "conditional",
+    "group",
+    "build",
+    "test",
+    "system-build",
+    "system-test",
+];
+
+/// Allowed dependencies between packages of different publishers.
+///
+/// Runtime dependency of package (or build/test dependency of component) on package of other
+/// publisher is allowed only if some rule allows it. Component has publisher of its packages.
+///
+/// Example of policy file (it is also the default policy):
+/// ```toml
+/// [[allow]]
+/// from = "openindiana.org"
+/// to = "hipster-encumbered"
+/// types = ["optional", "incorporate", "conditional", "require-any", "test", "system-test"]
+///
+/// # all types are allowed when types are missing
+/// [[allow]]
+/// from = "hipster-encumbered"
+/// to = "openindiana.org"
+/// ```
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PublisherPolicy {
+    #[serde(default)]
+    pub allow: Vec<AllowedDependency>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AllowedDependency {
+    /// publisher of dependent package or component
+    pub from: String,
+    /// publisher of dependency
+    pub to: String,
+    /// dependency types, all types if missing
+    #[serde(default)]
+    pub types: Option<Vec<String>>,
+}
+
+impl Default for PublisherPolicy {
+    fn default() -> Self {
+        Self {
+            allow: vec![
+                AllowedDependency {
+                    from: "openindiana.org".to_owned(),
+                    to: "hipster-encumbered".to_owned(),
+                    types: Some(
+                        [
+                            "optional",
+                            "incorporate",
+                            "conditional",
+                            "require-any",
+                            "test",
+                            "system-test",
+                        ]
+                        .map(|t| t.to_owned())
+                        .to_vec(),
+                    ),
+                },
+                AllowedDependency {
+                    from: "hipster-encumbered".to_owned(),
+                    to: "openindiana.org".to_owned(),
+                    types: None,
+                },
+            ],
+        }
+    }
+}
+
+impl PublisherPolicy {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read file {}: {}", path.display(), e))?;
+
+        let policy: Self = toml::from_str(&content)
+            .map_err(|e| format!("failed to parse publisher policy {}: {}", path.display(), e))?;
+
+        for t in policy.allow.iter().flat_map(|a| a.types.iter().flatten()) {
+            if !DEPENDENCY_TYPES.contains(&t.as_str()) {
+                return Err(format!(
+                    "unknown dependency type {} in publisher policy {}",
+                    t,
+                    path.display()
+                ));
+            }
+        }
+
+        Ok(policy)
+    }
+
+    /// Returns true if dependency of type (`require`, `build`, ...) from publisher to publisher is allowed
+    pub fn is_allowed(&self, from: &Publisher, to: &Publisher, dependency_type: &str) -> bool {
+        from == to
+            || self.allow.iter().any(|a| {
+                &a.from == from.get_as_ref_string()
+                    && &a.to == to.get_as_ref_string()
+                    && a.types
+                        .as_ref()
+                        .is_none_or(|types| types.iter().any(|t| t == dependency_type))
+            })
+    }
+}
+
+impl Components {
+    /// Adds [`PublisherPolicyViolation`] for every runtime dependency of non-obsolete package and
+    /// every build/test dependency of component which is not allowed by policy
+    pub fn check_publisher_policy(&mut self, policy: &PublisherPolicy) -> Result<(), String> {
+        let mut problems: Vec<Problem> = Vec::new();
+
+        let publisher_of = |fmri: &FMRI| -> Option<Publisher> {
+            get!(self.get_package_by_fmri(fmri).ok()?)
+                .get_publisher()
+                .clone()
+        };
+
+        for p in &self.packages {
+            let package = get!(p);
+            let from = match package.get_publisher() {
+                Some(publisher) if !package.is_obsolete() => publisher.clone(),
+                _ => continue,
+            };
+            let fmri = package.fmri.clone();
+            let dependencies = package
+                .get_effective_version()
+                .map(|v| v.get_runtime_dependencies().clone())
+                .unwrap_or_default();
+            drop(package);
+
+            for d in dependencies {
+                let targets = match &d {
+                    DependTypes::Require(f)
+                    | DependTypes::Optional(f)
+                    | DependTypes::Incorporate(f)
+                    | DependTypes::Group(f)
+                    | DependTypes::Conditional(f, _) => vec![f.clone()],
+                    DependTypes::RequireAny(l) | DependTypes::GroupAny(l) => l.get_ref().clone(),
+                    DependTypes::Exclude(_) | DependTypes::Origin(_) | DependTypes::Parent(_) => {
+                        continue
+                    }
+                };
+                let (name, _) = d.clone().get_name_and_content_as_string();
+
+                for target in targets.iter().filter(|t| !t.package_name_eq(&fmri)) {
+                    if let Some(to) = publisher_of(target) {
+                        if !policy.is_allowed(&from, &to, &name) {
+                            problems.push(PublisherPolicyViolation(
+                                d.clone(),
+                                Runtime,
+                                fmri.clone(),
+                                "".to_owned(),
+                                from.clone(),
+                                to,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        for c in &self.components {
+            let component = get!(c);
+            let from = match component.get_publisher() {
+                Some(publisher) => publisher,
+                None => continue,
+            };
+
+            for (dependencies, dependency_type) in [
+                (component.get_build_dependencies(), Build),
+                (component.get_test_dependencies(), Test),
+                (component.get_sys_build_dependencies(), SystemBuild),
+                (component.get_sys_test_dependencies(), SystemTest),
+            ] {
+                for p in dependencies {
+                    let fmri = get!(p.upgrade().unwrap()).fmri.clone();
+                    let to = match publisher_of(&fmri) {
+                        Some(publisher) => publisher,
+                        None => continue,
+                    };
+
+                    if !policy.is_allowed(&from, &to, &dependency_type.to_string()) {
+                        let mut fmri = fmri;
+                        fmri.remove_version();
+                        fmri.remove_publisher();
+
+                        problems.push(PublisherPolicyViolation(
+                            DependTypes::Require(fmri),
+                            dependency_type.clone(),
+                            FMRI::parse_raw("none")
+                                .map_err(|e| format!("failed to parse fmri: {}", e))?,
+                            component.get_name().clone(),
+                            from.clone(),
+                            to,
+                        ));
+                    }
+                }
+            }
+        }
+
+        for problem in problems {
+            self.problems.add_problem(problem);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,546 @@
+use std::ops::{BitAnd, BitOr, Not};
+
+use fmri::FMRI;
+
+use crate::{
+    get,
+    packages::{
+        components::Components, depend_types::DependTypes, dependency_type::DependencyTypes,
+        rev_depend_type::RevDependType,
+    },
+};
+
+/// Kind of dependency in `depends:` and `dependent:` predicates
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryDependency {
+    /// any runtime dependency
+    Runtime,
+    Require,
+    Optional,
+    Incorporate,
+    RequireAny,
+    Conditional,
+    Group,
+    /// build, test, system-build or system-test dependency of component
+    Component(DependencyTypes),
+}
+
+/// Predicate over packages or components.
+///
+/// Predicates can be combined with `&`, `|` and `!` or parsed from string by [`Query::parse`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Query {
+    Obsolete,
+    Renamed,
+    /// glob matched against package or component name
+    Name(String),
+    /// glob matched against name of component (package is in)
+    Component(String),
+    Publisher(String),
+    /// has dependency of given kind (on package matching query)
+    Depends(QueryDependency, Option<Box<Query>>),
+    /// has dependent of given kind (matching query)
+    Dependent(QueryDependency, Option<Box<Query>>),
+    /// component contains package matching query
+    Contains(Box<Query>),
+    /// has problem (of given kind, e.g. `obsoleted-required`)
+    Problem(Option<String>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+}
+
+impl BitAnd for Query {
+    type Output = Query;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Query::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl BitOr for Query {
+    type Output = Query;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Query::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Not for Query {
+    type Output = Query;
+
+    fn not(self) -> Self::Output {
+        Query::Not(Box::new(self))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+}
+
+impl Query {
+    /// Parses query.
+    ///
+    /// Predicates are `obsolete`, `renamed`, `name:<glob>`, `component:<glob>`,
+    /// `publisher:<name>`, `depends:<kind>`, `dependent:<kind>`, `contains(<query>)`, `problem`
+    /// and `problem:<kind>`. `depends` and `dependent` can be followed by `(<query>)` which has
+    /// to match the other side of dependency. Predicates are combined with `and`, `or`, `not`
+    /// and parentheses.
+    ///
+    /// # Example
+    ///
+    /// `name:library/python/* and depends:require(obsolete)`
+    pub fn parse(query: &str) -> Result<Query, String> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut word = String::new();
+        for c in query.chars() {
+            if c.is_whitespace() || c == '(' || c == ')' {
+                if !word.is_empty() {
+                    tokens.push(Token::Word(std::mem::take(&mut word)));
+                }
+                match c {
+                    '(' => tokens.push(Token::Open),
+                    ')' => tokens.push(Token::Close),
+                    _ => {}
+                }
+            } else {
+                word.push(c);
+            }
+        }
+        if !word.is_empty() {
+            tokens.push(Token::Word(word));
+        }
+
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let query = parser.or()?;
+        match parser.next() {
+            None => Ok(query),
+            Some(token) => Err(format!("unexpected {:?} in query", token)),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.peek() == Some(&Token::Word(word.to_owned()))
+    }
+
+    fn or(&mut self) -> Result<Query, String> {
+        let mut query = self.and()?;
+        while self.is_word("or") {
+            self.next();
+            query = query | self.and()?;
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, String> {
+        let mut query = self.not()?;
+        while self.is_word("and") {
+            self.next();
+            query = query & self.not()?;
+        }
+        Ok(query)
+    }
+
+    fn not(&mut self) -> Result<Query, String> {
+        if self.is_word("not") {
+            self.next();
+            return Ok(!self.not()?);
+        }
+        self.atom()
+    }
+
+    fn group(&mut self) -> Result<Query, String> {
+        let query = self.or()?;
+        match self.next() {
+            Some(Token::Close) => Ok(query),
+            _ => Err("missing ) in query".to_owned()),
+        }
+    }
+
+    /// parses `(<query>)` following predicate, if there is any
+    fn optional_group(&mut self) -> Result<Option<Box<Query>>, String> {
+        if self.peek() != Some(&Token::Open) {
+            return Ok(None);
+        }
+        self.next();
+        Ok(Some(Box::new(self.group()?)))
+    }
+
+    fn atom(&mut self) -> Result<Query, String> {
+        let word = match self.next() {
+            Some(Token::Open) => return self.group(),
+            Some(Token::Word(word)) => word,
+            Some(Token::Close) => return Err("unexpected ) in query".to_owned()),
+            None => return Err("unexpected end of query".to_owned()),
+        };
+
+        let (key, value) = match word.split_once(':') {
+            Some((key, value)) => (key, Some(value.to_owned())),
+            None => (word.as_str(), None),
+        };
+
+        let value = value.filter(|v| !v.is_empty());
+        let required = |predicate: &str| {
+            value
+                .clone()
+                .ok_or(format!("predicate {} needs value", predicate))
+        };
+
+        Ok(match key {
+            "obsolete" => Query::Obsolete,
+            "renamed" => Query::Renamed,
+            "name" => Query::Name(required("name")?),
+            "component" => Query::Component(required("component")?),
+            "publisher" => Query::Publisher(required("publisher")?),
+            "depends" => Query::Depends(
+                parse_dependency(&required("depends")?)?,
+                self.optional_group()?,
+            ),
+            "dependent" => Query::Dependent(
+                parse_dependency(&required("dependent")?)?,
+                self.optional_group()?,
+            ),
+            "contains" => match self.optional_group()? {
+                Some(query) => Query::Contains(query),
+                None => return Err("predicate contains needs (<query>)".to_owned()),
+            },
+            "problem" => Query::Problem(value),
+            _ => return Err(format!("unknown predicate {}", word)),
+        })
+    }
+}
+
+fn parse_dependency(kind: &str) -> Result<QueryDependency, String> {
+    Ok(match kind {
+        "runtime" => QueryDependency::Runtime,
+        "require" => QueryDependency::Require,
+        "optional" => QueryDependency::Optional,
+        "incorporate" => QueryDependency::Incorporate,
+        "require-any" => QueryDependency::RequireAny,
+        "conditional" => QueryDependency::Conditional,
+        "group" => QueryDependency::Group,
+        "build" => QueryDependency::Component(DependencyTypes::Build),
+        "test" => QueryDependency::Component(DependencyTypes::Test),
+        "system-build" => QueryDependency::Component(DependencyTypes::SystemBuild),
+        "system-test" => QueryDependency::Component(DependencyTypes::SystemTest),
+        _ => return Err(format!("unknown dependency type {}", kind)),
+    })
+}
+
+/// Returns targets of runtime dependency if it is of given kind
+fn dependency_targets(d: &DependTypes, kind: &QueryDependency) -> Option<Vec<FMRI>> {
+    let targets = match d {
+        DependTypes::Require(f)
+        | DependTypes::Optional(f)
+        | DependTypes::Incorporate(f)
+        | DependTypes::Group(f)
+        | DependTypes::Conditional(f, _)
+        | DependTypes::Exclude(f)
+        | DependTypes::Origin(f)
+        | DependTypes::Parent(f) => vec![f.clone()],
+        DependTypes::RequireAny(l) | DependTypes::GroupAny(l) => l.get_ref().clone(),
+    };
+
+    let matches = match kind {
+        QueryDependency::Runtime => true,
+        QueryDependency::Require => matches!(d, DependTypes::Require(_)),
+        QueryDependency::Optional => matches!(d, DependTypes::Optional(_)),
+        QueryDependency::Incorporate => matches!(d, DependTypes::Incorporate(_)),
+        QueryDependency::RequireAny => matches!(d, DependTypes::RequireAny(_)),
+        QueryDependency::Conditional => matches!(d, DependTypes::Conditional(_, _)),
+        QueryDependency::Group => matches!(d, DependTypes::Group(_)),
+        QueryDependency::Component(_) => false,
+    };
+
+    matches.then_some(targets)
+}
+
+/// Returns dependent package if reverse dependency is of given kind
+fn dependent_package(d: &RevDependType, kind: &QueryDependency) -> Option<FMRI> {
+    match (d, kind) {
+        (RevDependType::Require(f), QueryDependency::Require)
+        | (RevDependType::Optional(f), QueryDependency::Optional)
+        | (RevDependType::Incorporate(f), QueryDependency::Incorporate)
+        | (RevDependType::RequireAny(f), QueryDependency::RequireAny)
+        | (RevDependType::ConditionalFmri(f), QueryDependency::Conditional)
+        | (RevDependType::ConditionalPredicate(f), QueryDependency::Conditional)
+        | (RevDependType::Group(f), QueryDependency::Group) => Some(f.clone()),
+        (
+            RevDependType::Require(f)
+            | RevDependType::Optional(f)
+            | RevDependType::Incorporate(f)
+            | RevDependType::RequireAny(f)
+            | RevDependType::ConditionalFmri(f)
+            | RevDependType::ConditionalPredicate(f)
+            | RevDependType::Group(f),
+            QueryDependency::Runtime,
+        ) => Some(f.clone()),
+        _ => None,
+    }
+}
+
+/// Matches `text` against glob with `*` and `?`
+pub(crate) fn glob_match(glob: &str, text: &str) -> bool {
+    let (glob, text) = (glob.as_bytes(), text.as_bytes());
+    let (mut g, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match glob.get(g) {
+            Some(b'*') => {
+                backtrack = Some((g, t));
+                g += 1;
+            }
+            Some(c) if *c == b'?' || *c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((bg, bt)) => {
+                    g = bg + 1;
+                    t = bt + 1;
+                    backtrack = Some((bg, bt + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|c| *c == b'*')
+}
+
+impl Components {
+    /// Returns packages (without version) matching query, sorted by name
+    pub fn query_packages(&self, query: &Query) -> Result<Vec<FMRI>, String> {
+        let mut fmris: Vec<FMRI> = Vec::new();
+        for p in &self.packages {
+            let fmri = get!(p).fmri.clone();
+            if self.package_matches(&fmri, query)? {
+                fmris.push(fmri);
+            }
+        }
+        fmris.sort_by(|a, b| {
+            a.get_package_name_as_ref_string()
+                .cmp(b.get_package_name_as_ref_string())
+        });
+        Ok(fmris)
+    }
+
+    /// Returns names of components matching query, sorted
+    pub fn query_components(&self, query: &Query) -> Result<Vec<String>, String> {
+        let mut names: Vec<String> = Vec::new();
+        for c in &self.components {
+            let name = get!(c).get_name().clone();
+            if self.component_matches(&name, query)? {
+                names.push(name);
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    fn package_matches(&self, fmri: &FMRI, query: &Query) -> Result<bool, String> {
+        // borrow of package must not be held during evaluation of nested query
+        let (dependencies, dependents, component) = {
+            let package = get!(self.get_package_by_fmri(fmri)?);
+
+            match query {
+                Query::Obsolete => return Ok(package.is_obsolete()),
+                Query::Renamed => return Ok(package.is_renamed()),
+                Query::Name(glob) => {
+                    return Ok(glob_match(
+                        glob.trim_start_matches("pkg:/"),
+                        package.fmri.get_package_name_as_ref_string(),
+                    ))
+                }
+                Query::Publisher(publisher) => {
+                    return Ok(package.fmri.get_publisher_as_ref_string() == Some(publisher))
+                }
+                _ => {}
+            }
+
+            (
+                package
+                    .get_effective_version()
+                    .map(|v| v.get_runtime_dependencies().clone())
+                    .unwrap_or_default(),
+                package.get_runtime_dependents().clone(),
+                package.is_in_component().clone(),
+            )
+        };
+
+        Ok(match query {
+            Query::Component(glob) => {
+                component.is_some_and(|c| glob_match(glob, get!(c).get_name()))
+            }
+            Query::Depends(QueryDependency::Component(kind), _) => {
+                return Err(format!("package has no {} dependencies", kind))
+            }
+            Query::Depends(kind, sub_query) => {
+                for targets in dependencies
+                    .iter()
+                    .filter_map(|d| dependency_targets(d, kind))
+                {
+                    let sub_query = match sub_query {
+                        None => return Ok(true),
+                        Some(q) => q,
+                    };
+
+                    for target in targets {
+                        if self.get_package_by_fmri(&target).is_ok()
+                            && self.package_matches(&target, sub_query)?
+                        {
+                            return Ok(true);
+                        }
+                    }
+                }
+                false
+            }
+            Query::Dependent(QueryDependency::Component(kind), sub_query) => {
+                let names = get!(self.get_package_by_fmri(fmri)?)
+                    .get_git_dependents(kind.clone())?
+                    .iter()
+                    .map(|c| get!(c).get_name().clone())
+                    .collect::<Vec<String>>();
+
+                match sub_query {
+                    None => !names.is_empty(),
+                    Some(q) => {
+                        for name in names {
+                            if self.component_matches(&name, q)? {
+                                return Ok(true);
+                            }
+                        }
+                        false
+                    }
+                }
+            }
+            Query::Dependent(kind, sub_query) => {
+                for dependent in dependents.iter().filter_map(|d| dependent_package(d, kind)) {
+                    match sub_query {
+                        None => return Ok(true),
+                        Some(q) => {
+                            if self.get_package_by_fmri(&dependent).is_ok()
+                                && self.package_matches(&dependent, q)?
+                            {
+                                return Ok(true);
+                            }
+                        }
+                    }
+                }
+                false
+            }
+            Query::Contains(_) => return Err("contains can be used only for components".to_owned()),
+            Query::Problem(kind) => self
+                .problems
+                .get_problems_related_to_fmri(fmri)
+                .iter()
+                .any(|p| kind.as_ref().is_none_or(|k| p.kind() == k)),
+            Query::And(a, b) => self.package_matches(fmri, a)? && self.package_matches(fmri, b)?,
+            Query::Or(a, b) => self.package_matches(fmri, a)? || self.package_matches(fmri, b)?,
+            Query::Not(a) => !self.package_matches(fmri, a)?,
+            Query::Obsolete | Query::Renamed | Query::Name(_) | Query::Publisher(_) => {
+                unreachable!()
+            }
+        })
+    }
+
+    fn component_matches(&self, name: &String, query: &Query) -> Result<bool, String> {
+        Ok(match query {
+            Query::Name(glob) | Query::Component(glob) => glob_match(glob, name),
+            Query::Depends(QueryDependency::Component(kind), sub_query) => {
+                let dependencies = self.get_component_fmris(name, Some(kind))?;
+
+                match sub_query {
+                    None => !dependencies.is_empty(),
+                    Some(q) => {
+                        for fmri in dependencies {
+                            if self.package_matches(&fmri, q)? {
+                                return Ok(true);
+                            }
+                        }
+                        false
+                    }
+                }
+            }
+            Query::Contains(sub_query) => {
+                let contained = self.get_component_fmris(name, None)?;
+
+                for fmri in contained {
+                    if self.package_matches(&fmri, sub_query)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            Query::Problem(kind) => self
+                .problems
+                .get_problems_related_to_component(name)
+                .iter()
+                .any(|p| kind.as_ref().is_none_or(|k| p.kind() == k)),
+            Query::And(a, b) => {
+                self.component_matches(name, a)? && self.component_matches(name, b)?
+            }
+            Query::Or(a, b) => {
+                self.component_matches(name, a)? || self.component_matches(name, b)?
+            }
+            Query::Not(a) => !self.component_matches(name, a)?,
+            Query::Obsolete
+            | Query::Renamed
+            | Query::Publisher(_)
+            | Query::Depends(_, _)
+            | Query::Dependent(_, _) => {
+                return Err(
+                    "predicate can be used only for packages, use contains(<query>)".to_owned(),
+                )
+            }
+        })
+    }
+
+    /// Returns packages of component or its dependencies of given type
+    fn get_component_fmris(
+        &self,
+        name: &String,
+        dependency_type: Option<&DependencyTypes>,
+    ) -> Result<Vec<FMRI>, String> {
+        let component = get!(self.get_component_by_name(name)?);
+        let packages = match dependency_type {
+            None => &component.packages,
+            Some(DependencyTypes::Build) => component.get_build_dependencies(),
+            Some(DependencyTypes::Test) => component.get_test_dependencies(),
+            Some(DependencyTypes::SystemBuild) => component.get_sys_build_dependencies(),
+            Some(DependencyTypes::SystemTest) => component.get_sys_test_dependencies(),
+            Some(DependencyTypes::Runtime) => {
+                return Err("component has no runtime dependencies".to_owned())
+            }
+        };
+
+        Ok(packages
+            .iter()
+            .map(|p| get!(p.upgrade().unwrap()).fmri.clone())
+            .collect())
+    }
+}
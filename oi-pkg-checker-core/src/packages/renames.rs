@@ -0,0 +1,75 @@
+use fmri::FMRI;
+
+use crate::{get, packages::components::Components};
+
+/// Result of following rename targets from package
+#[derive(Clone, Debug, PartialEq)]
+pub enum RenameChain {
+    /// chain ends with package which is not renamed (last one)
+    Resolved(Vec<FMRI>),
+    /// chain ends with renamed package without rename target
+    Unknown(Vec<FMRI>),
+    /// last package in chain does not exist
+    NonExisting(Vec<FMRI>),
+    /// packages in loop, starting with the smallest fmri
+    Loop(Vec<FMRI>),
+}
+
+impl Components {
+    /// Follows rename targets starting with package `fmri` (A -> B -> C).
+    ///
+    /// Returned chain contains `fmri` as first item.
+    pub fn get_rename_chain(&self, fmri: &FMRI) -> Result<RenameChain, String> {
+        let mut fmri = fmri.clone();
+        fmri.remove_version();
+
+        let mut chain: Vec<FMRI> = Vec::new();
+
+        loop {
+            if let Some(position) = chain.iter().position(|f| f.package_name_eq(&fmri)) {
+                let mut rename_loop = chain.split_off(position);
+                let min = rename_loop
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| {
+                        a.get_package_name_as_ref_string()
+                            .cmp(b.get_package_name_as_ref_string())
+                    })
+                    .map(|(i, _)| i)
+                    .unwrap();
+                rename_loop.rotate_left(min);
+                return Ok(RenameChain::Loop(rename_loop));
+            }
+
+            let package = match self.get_package_by_fmri(&fmri) {
+                Ok(p) => get!(p),
+                Err(_) if chain.is_empty() => {
+                    return Err(format!("package {} does not exist", fmri))
+                }
+                Err(_) => {
+                    chain.push(fmri);
+                    return Ok(RenameChain::NonExisting(chain));
+                }
+            };
+
+            chain.push(fmri.clone());
+
+            if !package.is_renamed() {
+                return Ok(RenameChain::Resolved(chain));
+            }
+
+            fmri = match package.get_renamed_to() {
+                None => return Ok(RenameChain::Unknown(chain)),
+                Some(f) => f.clone(),
+            };
+        }
+    }
+
+    /// Returns the final package of rename chain, if the chain can be resolved
+    pub fn resolve_rename(&self, fmri: &FMRI) -> Option<FMRI> {
+        match self.get_rename_chain(fmri) {
+            Ok(RenameChain::Resolved(chain)) => chain.last().cloned(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+};
+
+use fmri::FMRI;
+
+use crate::{get, packages::components::Components, DependTypes};
+
+/// Language with modules packaged per interpreter version
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Language {
+    Python,
+    Perl,
+    Ruby,
+}
+
+impl Language {
+    const ALL: [Language; 3] = [Language::Python, Language::Perl, Language::Ruby];
+
+    /// prefix of module packages, e.g. `library/python/foo-311`
+    fn module_prefix(&self) -> &'static str {
+        match self {
+            Language::Python => "library/python/",
+            Language::Perl => "library/perl-5/",
+            Language::Ruby => "library/ruby/",
+        }
+    }
+
+    /// prefix of interpreter packages, e.g. `runtime/python-311`
+    fn runtime_prefix(&self) -> &'static str {
+        match self {
+            Language::Python => "runtime/python-",
+            Language::Perl => "runtime/perl-",
+            Language::Ruby => "runtime/ruby-",
+        }
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Language::Python => "python",
+                Language::Perl => "perl",
+                Language::Ruby => "ruby",
+            }
+        )
+    }
+}
+
+/// Module (package name without version suffix) of language
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Module {
+    pub language: Language,
+    /// e.g. `library/python/foo`
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeMatrix {
+    /// versions of non-obsolete interpreters
+    pub supported: BTreeMap<Language, Vec<String>>,
+    /// versions of obsolete interpreters
+    pub obsolete: BTreeMap<Language, Vec<String>>,
+    /// modules and supported versions they are missing for
+    pub missing: Vec<(Module, Vec<String>)>,
+    /// modules published only for unsupported interpreters, with these versions
+    pub only_obsolete: Vec<(Module, Vec<String>)>,
+    /// version-suffixed package and its dependency on other interpreter version
+    pub mismatched: Vec<(FMRI, FMRI)>,
+}
+
+/// Splits package name into language, base name and interpreter version
+fn split_versioned(name: &str) -> Option<(Language, String, String)> {
+    for language in Language::ALL {
+        if let Some(version) = name.strip_prefix(language.runtime_prefix()) {
+            if is_version(version) {
+                return Some((
+                    language,
+                    language.runtime_prefix().to_owned(),
+                    version.to_owned(),
+                ));
+            }
+        }
+
+        if name.starts_with(language.module_prefix()) {
+            let (base, version) = name.rsplit_once('-')?;
+            if is_version(version) {
+                return Some((language, base.to_owned(), version.to_owned()));
+            }
+        }
+    }
+    None
+}
+
+fn is_version(version: &str) -> bool {
+    !version.is_empty() && version.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Sorts versions numerically
+fn sort_versions(versions: &mut [String]) {
+    versions.sort_by_key(|v| v.parse::<u64>().unwrap_or_default())
+}
+
+impl Components {
+    /// Checks modules of interpreted languages against interpreter versions in catalog.
+    ///
+    /// Interpreter version is supported if its package (e.g. `runtime/python-311`) is neither
+    /// obsolete nor renamed. Module is published for version if its package with that suffix
+    /// is neither obsolete nor renamed.
+    pub fn get_runtime_matrix(&self) -> RuntimeMatrix {
+        let mut supported: BTreeMap<Language, Vec<String>> = BTreeMap::new();
+        let mut obsolete: BTreeMap<Language, Vec<String>> = BTreeMap::new();
+        let mut modules: BTreeMap<Module, BTreeSet<String>> = BTreeMap::new();
+        let mut mismatched: Vec<(FMRI, FMRI)> = Vec::new();
+
+        for p in &self.packages {
+            let package = get!(p);
+            let name = package.fmri.get_package_name_as_ref_string();
+            let (language, base, version) = match split_versioned(name) {
+                Some(split) => split,
+                None => continue,
+            };
+            let published = !package.is_obsolete() && !package.is_renamed();
+
+            if base == language.runtime_prefix() {
+                match published {
+                    true => supported.entry(language).or_default().push(version),
+                    false => obsolete.entry(language).or_default().push(version),
+                }
+                continue;
+            }
+
+            if !published {
+                continue;
+            }
+
+            for d in package
+                .get_effective_version()
+                .map(|v| v.get_runtime_dependencies().clone())
+                .unwrap_or_default()
+            {
+                let target = match d {
+                    DependTypes::Require(f)
+                    | DependTypes::Optional(f)
+                    | DependTypes::Group(f)
+                    | DependTypes::Conditional(f, _) => f,
+                    _ => continue,
+                };
+
+                if let Some((l, _, v)) = split_versioned(target.get_package_name_as_ref_string()) {
+                    if l == language && v != version {
+                        mismatched.push((package.fmri.clone(), target));
+                    }
+                }
+            }
+
+            modules
+                .entry(Module {
+                    language,
+                    name: base,
+                })
+                .or_default()
+                .insert(version);
+        }
+
+        for versions in supported.values_mut().chain(obsolete.values_mut()) {
+            sort_versions(versions);
+        }
+
+        let mut missing: Vec<(Module, Vec<String>)> = Vec::new();
+        let mut only_obsolete: Vec<(Module, Vec<String>)> = Vec::new();
+
+        for (module, versions) in modules {
+            // interpreter is not in catalog at all
+            if !supported.contains_key(&module.language) && !obsolete.contains_key(&module.language)
+            {
+                continue;
+            }
+
+            let supported_versions = supported.get(&module.language).cloned().unwrap_or_default();
+
+            if versions.iter().any(|v| supported_versions.contains(v)) {
+                let not_published = supported_versions
+                    .into_iter()
+                    .filter(|v| !versions.contains(v))
+                    .collect::<Vec<String>>();
+
+                if !not_published.is_empty() {
+                    missing.push((module, not_published));
+                }
+            } else {
+                let mut versions = versions.into_iter().collect::<Vec<String>>();
+                sort_versions(&mut versions);
+                only_obsolete.push((module, versions));
+            }
+        }
+
+        mismatched.sort_by(|(a, _), (b, _)| {
+            a.get_package_name_as_ref_string()
+                .cmp(b.get_package_name_as_ref_string())
+        });
+
+        RuntimeMatrix {
+            supported,
+            obsolete,
+            missing,
+            only_obsolete,
+            mismatched,
+        }
+    }
+}
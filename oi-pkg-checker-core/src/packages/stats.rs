@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fmri::FMRI;
+use serde::Serialize;
+
+use crate::{
+    get,
+    packages::{components::Components, cycles::strongly_connected_components},
+    DependTypes,
+};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PackageRank {
+    pub package: String,
+    /// number of packages which depend directly on package
+    pub direct: usize,
+    /// number of packages which depend (directly or transitively) on package
+    pub transitive: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ComponentRank {
+    pub component: String,
+    /// number of packages in build environment of component
+    pub build_closure: usize,
+}
+
+/// Metrics of dependency graph, obsolete packages are not counted except in `publishers`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub packages: usize,
+    pub components: usize,
+    pub most_depended_upon: Vec<PackageRank>,
+    pub largest_build_closures: Vec<ComponentRank>,
+    /// (number of direct dependents, number of packages), buckets grow in powers of two
+    pub fan_in: Vec<(String, usize)>,
+    /// (number of direct dependencies, number of packages), buckets grow in powers of two
+    pub fan_out: Vec<(String, usize)>,
+    /// the longest chains of runtime dependencies (packages in cycle are counted once)
+    pub longest_chains: Vec<Vec<String>>,
+    /// number of packages of every publisher
+    pub publishers: BTreeMap<String, usize>,
+}
+
+impl Stats {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("failed to serialize stats: {}", e))
+    }
+}
+
+impl Components {
+    /// Computes metrics of dependency graph, lists contain at most `top` items
+    pub fn get_stats(&self, top: usize) -> Result<Stats, String> {
+        let graph = self.get_runtime_graph();
+
+        let mut dependents: BTreeMap<&String, usize> = graph.keys().map(|name| (name, 0)).collect();
+        for dependencies in graph.values() {
+            for dependency in dependencies {
+                *dependents.get_mut(dependency).unwrap() += 1;
+            }
+        }
+
+        // strongly connected components are sorted so that dependencies go first
+        let sccs = strongly_connected_components(&graph);
+        let mut scc_of: BTreeMap<&String, usize> = BTreeMap::new();
+        for (i, scc) in sccs.iter().enumerate() {
+            for name in scc {
+                scc_of.insert(name, i);
+            }
+        }
+
+        let mut scc_dependencies: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); sccs.len()];
+        for (name, dependencies) in &graph {
+            let a = scc_of[name];
+            for b in dependencies.iter().map(|d| scc_of[d]).filter(|b| *b != a) {
+                scc_dependencies[a].insert(b);
+            }
+        }
+
+        // transitive dependents, from the last (top level) component to dependencies
+        let words = sccs.len().div_ceil(64);
+        let mut reached_by: Vec<Vec<u64>> = vec![vec![0; words]; sccs.len()];
+        for a in (0..sccs.len()).rev() {
+            for b in scc_dependencies[a].clone() {
+                let (low, high) = reached_by.split_at_mut(a);
+                let (from, to) = (&high[0], &mut low[b]);
+                for (t, f) in to.iter_mut().zip(from) {
+                    *t |= *f;
+                }
+                to[a / 64] |= 1 << (a % 64);
+            }
+        }
+
+        let mut most_depended_upon = graph
+            .keys()
+            .map(|name| {
+                let s = scc_of[name];
+                let transitive = reached_by[s]
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(w, bits)| {
+                        (0..64)
+                            .filter(move |i| bits & (1 << i) != 0)
+                            .map(move |i| w * 64 + i)
+                    })
+                    .map(|i| sccs[i].len())
+                    .sum::<usize>()
+                    + sccs[s].len()
+                    - 1;
+
+                PackageRank {
+                    package: name.clone(),
+                    direct: dependents[name],
+                    transitive,
+                }
+            })
+            .collect::<Vec<PackageRank>>();
+        most_depended_upon.sort_by(|a, b| {
+            b.transitive
+                .cmp(&a.transitive)
+                .then(b.direct.cmp(&a.direct))
+                .then(a.package.cmp(&b.package))
+        });
+        most_depended_upon.truncate(top);
+
+        // the longest chain starting in every strongly connected component
+        let mut length: Vec<usize> = vec![0; sccs.len()];
+        let mut next: Vec<Option<usize>> = vec![None; sccs.len()];
+        for a in 0..sccs.len() {
+            let longest = scc_dependencies[a].iter().max_by_key(|b| length[**b]);
+            length[a] = 1 + longest.map_or(0, |b| length[*b]);
+            next[a] = longest.cloned();
+        }
+
+        let mut starts = (0..sccs.len()).collect::<Vec<usize>>();
+        starts.sort_by(|a, b| length[*b].cmp(&length[*a]).then(a.cmp(b)));
+        let longest_chains = starts
+            .into_iter()
+            .take(top)
+            .map(|mut s| {
+                let mut chain = vec![sccs[s].iter().min().unwrap().clone()];
+                while let Some(n) = next[s] {
+                    chain.push(sccs[n].iter().min().unwrap().clone());
+                    s = n;
+                }
+                chain
+            })
+            .collect();
+
+        let mut largest_build_closures: Vec<ComponentRank> = Vec::new();
+        for c in &self.components {
+            let name = get!(c).get_name().clone();
+            largest_build_closures.push(ComponentRank {
+                build_closure: self.get_build_env(&name, false)?.packages.len(),
+                component: name,
+            });
+        }
+        largest_build_closures.sort_by(|a, b| {
+            b.build_closure
+                .cmp(&a.build_closure)
+                .then(a.component.cmp(&b.component))
+        });
+        largest_build_closures.truncate(top);
+
+        let mut publishers: BTreeMap<String, usize> = BTreeMap::new();
+        for p in &self.packages {
+            let publisher = get!(p)
+                .fmri
+                .get_publisher_as_ref_string()
+                .cloned()
+                .unwrap_or("none".to_owned());
+            *publishers.entry(publisher).or_default() += 1;
+        }
+
+        Ok(Stats {
+            packages: graph.len(),
+            components: self.components.len(),
+            most_depended_upon,
+            largest_build_closures,
+            fan_in: histogram(dependents.values().cloned()),
+            fan_out: histogram(graph.values().map(|d| d.len())),
+            longest_chains,
+            publishers,
+        })
+    }
+
+    /// Returns runtime dependencies (except incorporate) between non-obsolete packages
+    fn get_runtime_graph(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        let name_of = |fmri: &FMRI| -> Option<String> {
+            let package = get!(self.get_package_by_fmri(fmri).ok()?);
+            match package.is_obsolete() {
+                true => None,
+                false => Some(package.fmri.get_package_name_as_ref_string().clone()),
+            }
+        };
+
+        for p in &self.packages {
+            let package = get!(p);
+            if package.is_obsolete() {
+                continue;
+            }
+
+            let name = package.fmri.get_package_name_as_ref_string().clone();
+            let mut dependencies: BTreeSet<String> = BTreeSet::new();
+
+            for d in package
+                .get_effective_version()
+                .map(|v| v.get_runtime_dependencies().clone())
+                .unwrap_or_default()
+            {
+                let fmris = match d {
+                    DependTypes::Require(f)
+                    | DependTypes::Optional(f)
+                    | DependTypes::Conditional(f, _)
+                    | DependTypes::Group(f) => vec![f],
+                    DependTypes::RequireAny(l) => l.get(),
+                    _ => continue,
+                };
+
+                for f in fmris.iter().filter(|f| !f.package_name_eq(&package.fmri)) {
+                    if let Some(dependency) = name_of(f) {
+                        dependencies.insert(dependency);
+                    }
+                }
+            }
+
+            graph.insert(name, dependencies);
+        }
+
+        graph
+    }
+}
+
+/// Counts values in buckets 0, 1, 2-3, 4-7, ...
+fn histogram(values: impl Iterator<Item = usize>) -> Vec<(String, usize)> {
+    let mut buckets: BTreeMap<u32, usize> = BTreeMap::new();
+    for value in values {
+        let bucket = match value {
+            0 => 0,
+            v => v.ilog2() + 1,
+        };
+        *buckets.entry(bucket).or_default() += 1;
+    }
+
+    buckets
+        .into_iter()
+        .map(|(bucket, count)| {
+            let label = match bucket {
+                0 => "0".to_owned(),
+                1 => "1".to_owned(),
+                b => format!("{}-{}", 1usize << (b - 1), (1usize << b) - 1),
+            };
+            (label, count)
+        })
+        .collect()
+}
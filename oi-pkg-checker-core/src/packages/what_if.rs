@@ -0,0 +1,239 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use fmri::FMRI;
+use serde::Deserialize;
+
+use crate::{
+    assets::{catalog_update::load_catalog_update, p5p_archive::load_archive},
+    get_mut,
+    packages::{
+        checks::CheckOptions,
+        dependency_type::DependencyTypes::{Build, SystemBuild, SystemTest, Test},
+        fix::makefile_variable,
+    },
+    problems::Problem,
+    Components,
+};
+
+/// Planned changes which are applied on top of loaded data.
+///
+/// Example of plan file:
+/// ```toml
+/// obsolete = ["library/libfoo"]
+/// remove = ["library/libbar"]
+///
+/// [[rename]]
+/// fmri = "library/old"
+/// renamed_to = "library/new"
+///
+/// [[required_packages]]
+/// component = "foo"
+/// variable = "REQUIRED_PACKAGES"
+/// add = ["library/new"]
+/// remove = ["library/old"]
+/// ```
+#[derive(Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Plan {
+    /// packages (or versions of packages) which will be obsoleted
+    pub obsolete: Vec<String>,
+    /// packages which will be renamed
+    pub rename: Vec<PlannedRename>,
+    /// packages which will be removed from pkg5 repository
+    pub remove: Vec<String>,
+    /// changes of required packages in component Makefiles
+    pub required_packages: Vec<PlannedRequiredPackages>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PlannedRename {
+    pub fmri: String,
+    pub renamed_to: String,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PlannedRequiredPackages {
+    pub component: String,
+    /// Makefile variable, REQUIRED_PACKAGES by default
+    #[serde(default = "default_variable")]
+    pub variable: String,
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+fn default_variable() -> String {
+    "REQUIRED_PACKAGES".to_owned()
+}
+
+impl Plan {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read file {}: {}", path.display(), e))?;
+
+        toml::from_str(&content)
+            .map_err(|e| format!("failed to parse plan {}: {}", path.display(), e))
+    }
+}
+
+/// Difference between problems of unmodified and modified data
+#[derive(Clone, Debug, Default)]
+pub struct ProblemsDiff {
+    /// problems which appear after applying plan
+    pub added: Vec<Problem>,
+    /// problems which disappear after applying plan
+    pub resolved: Vec<Problem>,
+}
+
+impl Components {
+    /// Applies `plan` on copy of data and returns how problems change
+    pub fn what_if(&self, plan: &Plan, options: &CheckOptions) -> Result<ProblemsDiff, String> {
+        let mut before = self.deep_clone();
+        before.remove_checked_problems(options)?;
+
+        let mut after = before.deep_clone();
+        after.apply_plan(plan)?;
+
+        before.check_all(options)?;
+        after.check_all(options)?;
+
+        let before = before.problems.get_ref();
+        let after = after.problems.get_ref();
+
+        Ok(ProblemsDiff {
+            added: after
+                .iter()
+                .filter(|p| !before.contains(p))
+                .cloned()
+                .collect(),
+            resolved: before
+                .iter()
+                .filter(|p| !after.contains(p))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Loads p5p archives over checked data and returns how problems change
+    pub fn overlay_archives(
+        &mut self,
+        archives: &[PathBuf],
+        options: &CheckOptions,
+    ) -> Result<ProblemsDiff, String> {
+        self.recheck_after(options, |components| {
+            for path in archives {
+                load_archive(components, path)
+                    .map_err(|e| format!("failed to load archive {}: {}", path.display(), e))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Applies incremental catalog updates (in order of their file names) on checked data and
+    /// returns how problems change
+    pub fn apply_catalog_updates(
+        &mut self,
+        updates: &[PathBuf],
+        options: &CheckOptions,
+    ) -> Result<ProblemsDiff, String> {
+        let mut updates = updates.to_vec();
+        updates.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+        self.recheck_after(options, |components| {
+            for path in &updates {
+                load_catalog_update(components, path)
+                    .map_err(|e| format!("failed to apply update {}: {}", path.display(), e))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Changes checked data, runs all checks again and returns how problems change
+    fn recheck_after<F: FnOnce(&mut Self) -> Result<(), String>>(
+        &mut self,
+        options: &CheckOptions,
+        change: F,
+    ) -> Result<ProblemsDiff, String> {
+        let before = self.problems.get_ref().clone();
+
+        self.remove_checked_problems(options)?;
+        change(self)?;
+        self.link_added_packages()?;
+        self.check_all(options)?;
+
+        let after = self.problems.get_ref();
+
+        Ok(ProblemsDiff {
+            added: after
+                .iter()
+                .filter(|p| !before.contains(p))
+                .cloned()
+                .collect(),
+            resolved: before
+                .iter()
+                .filter(|p| !after.contains(p))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Applies `plan` on data, problems are not checked
+    pub fn apply_plan(&mut self, plan: &Plan) -> Result<(), String> {
+        let parse = |fmris: &Vec<String>| -> Result<Vec<FMRI>, String> {
+            fmris
+                .iter()
+                .map(|f| FMRI::parse_raw(f).map_err(|e| format!("failed to parse fmri: {}", e)))
+                .collect()
+        };
+
+        for change in &plan.required_packages {
+            let dependency_type = [Build, Test, SystemBuild, SystemTest]
+                .into_iter()
+                .find(|d| makefile_variable(d) == change.variable)
+                .ok_or(format!("unknown Makefile variable {}", change.variable))?;
+
+            self.remove_repo_dependencies(
+                &change.component,
+                parse(&change.remove)?,
+                &dependency_type,
+            )
+            .map_err(|e| format!("failed to remove dependencies: {}", e))?;
+            self.add_repo_dependencies(&change.component, parse(&change.add)?, &dependency_type)
+                .map_err(|e| format!("failed to add dependencies: {}", e))?;
+        }
+
+        for fmri in parse(&plan.remove)? {
+            self.remove_package(&fmri)
+                .map_err(|e| format!("failed to remove package {}: {}", fmri, e))?;
+        }
+
+        for fmri in parse(&plan.obsolete)? {
+            if fmri.has_version() {
+                self.set_package_obsolete(fmri.clone())
+            } else {
+                self.obsolete_whole_package(&fmri)
+            }
+            .map_err(|e| format!("failed to obsolete package {}: {}", fmri, e))?;
+        }
+
+        for rename in &plan.rename {
+            let fmri = FMRI::parse_raw(&rename.fmri)
+                .map_err(|e| format!("failed to parse fmri: {}", e))?;
+            let mut renamed_to = FMRI::parse_raw(&rename.renamed_to)
+                .map_err(|e| format!("failed to parse fmri: {}", e))?;
+            renamed_to.remove_version();
+
+            self.set_package_renamed(fmri.clone(), None)
+                .map_err(|e| format!("failed to rename package {}: {}", fmri, e))?;
+            get_mut!(self.get_package_by_fmri(&fmri)?).set_renamed_to(Some(renamed_to));
+        }
+
+        Ok(())
+    }
+}
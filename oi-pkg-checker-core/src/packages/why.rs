@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use fmri::FMRI;
+
+use crate::{
+    get,
+    packages::{
+        components::Components,
+        depend_types::DependTypes,
+        dependency_type::{
+            DependencyTypes,
+            DependencyTypes::{Build, SystemBuild, SystemTest, Test},
+        },
+    },
+};
+
+/// Endpoint or hop of dependency path
+#[derive(Clone, Debug, PartialEq)]
+pub enum WhyNode {
+    /// contains no version
+    Package(FMRI),
+    Component(String),
+}
+
+/// How one node depends on the next one
+#[derive(Clone, Debug, PartialEq)]
+pub enum WhyEdge {
+    /// runtime dependency of package
+    Runtime(Box<DependTypes>),
+    /// component contains package
+    Contains,
+    /// build or test dependency of component
+    Component(DependencyTypes),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WhyPath {
+    pub nodes: Vec<WhyNode>,
+    /// `edges[i]` leads from `nodes[i]` to `nodes[i + 1]`
+    pub edges: Vec<WhyEdge>,
+}
+
+impl WhyNode {
+    fn key(&self) -> String {
+        match self {
+            WhyNode::Package(f) => format!("pkg:/{}", f.get_package_name_as_ref_string()),
+            WhyNode::Component(name) => format!("component:{}", name),
+        }
+    }
+}
+
+impl Components {
+    /// Parses endpoint of `why`.
+    ///
+    /// `pkg:/name` is always package and `component:name` is always component, otherwise
+    /// package is preferred over component with the same name.
+    pub fn get_why_node(&self, name: &str) -> Result<WhyNode, String> {
+        if let Some(component) = name.strip_prefix("component:") {
+            self.get_component_by_name(&component.to_owned())?;
+            return Ok(WhyNode::Component(component.to_owned()));
+        }
+
+        match FMRI::parse_raw(name) {
+            Ok(fmri) if self.get_package_by_fmri(&fmri).is_ok() => {
+                return Ok(WhyNode::Package(
+                    get!(self.get_package_by_fmri(&fmri)?).fmri.clone(),
+                ))
+            }
+            _ if name.starts_with("pkg:/") => {
+                return Err(format!("package {} does not exist", name))
+            }
+            _ => {}
+        }
+
+        match self.get_component_by_name(&name.to_owned()) {
+            Ok(_) => Ok(WhyNode::Component(name.to_owned())),
+            Err(_) => Err(format!("package or component {} does not exist", name)),
+        }
+    }
+
+    /// Returns name of component which contains package
+    pub fn get_owning_component(&self, fmri: &FMRI) -> Option<String> {
+        let package = get!(self.get_package_by_fmri(fmri).ok()?);
+        let component = package.is_in_component().as_ref()?;
+        let name = get!(component).get_name().clone();
+        Some(name)
+    }
+
+    /// Finds up to `k` shortest paths (without repeated nodes) from `from` to `to`.
+    ///
+    /// Component as `to` is reached by any of its packages.
+    pub fn why(&self, from: &WhyNode, to: &WhyNode, k: usize) -> Result<Vec<WhyPath>, String> {
+        let mut paths: Vec<WhyPath> = Vec::new();
+        let mut candidates: Vec<WhyPath> = Vec::new();
+
+        match self.shortest_path(from, to, &HashSet::new(), &HashSet::new())? {
+            Some(path) => paths.push(path),
+            None => return Ok(paths),
+        }
+
+        // Yen's algorithm
+        while paths.len() < k {
+            let previous = paths.last().unwrap().clone();
+
+            for i in 0..previous.edges.len() {
+                let spur = &previous.nodes[i];
+                let root = &previous.nodes[..=i];
+
+                let removed_edges = paths
+                    .iter()
+                    .filter(|p| p.nodes.len() > i + 1 && p.nodes[..=i] == *root)
+                    .map(|p| (p.nodes[i].key(), p.nodes[i + 1].key()))
+                    .collect::<HashSet<(String, String)>>();
+                let removed_nodes = root[..i]
+                    .iter()
+                    .map(|n| n.key())
+                    .collect::<HashSet<String>>();
+
+                if let Some(spur_path) =
+                    self.shortest_path(spur, to, &removed_edges, &removed_nodes)?
+                {
+                    let mut path = WhyPath {
+                        nodes: root[..i].to_vec(),
+                        edges: previous.edges[..i].to_vec(),
+                    };
+                    path.nodes.extend(spur_path.nodes);
+                    path.edges.extend(spur_path.edges);
+
+                    if !paths.contains(&path) && !candidates.contains(&path) {
+                        candidates.push(path);
+                    }
+                }
+            }
+
+            if candidates.is_empty() {
+                break;
+            }
+
+            let shortest = candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, p)| p.edges.len())
+                .map(|(i, _)| i)
+                .unwrap();
+            paths.push(candidates.remove(shortest));
+        }
+
+        Ok(paths)
+    }
+
+    fn shortest_path(
+        &self,
+        from: &WhyNode,
+        to: &WhyNode,
+        removed_edges: &HashSet<(String, String)>,
+        removed_nodes: &HashSet<String>,
+    ) -> Result<Option<WhyPath>, String> {
+        let mut parents: HashMap<String, (WhyNode, WhyEdge)> = HashMap::new();
+        let mut queue: VecDeque<WhyNode> = VecDeque::from([from.clone()]);
+        let mut visited: HashSet<String> = HashSet::from([from.key()]);
+
+        while let Some(node) = queue.pop_front() {
+            if node != *from && self.is_why_target(&node, to) {
+                let mut path = WhyPath {
+                    nodes: vec![node.clone()],
+                    edges: Vec::new(),
+                };
+
+                let mut key = node.key();
+                while let Some((parent, edge)) = parents.get(&key) {
+                    path.nodes.push(parent.clone());
+                    path.edges.push(edge.clone());
+                    key = parent.key();
+                }
+
+                path.nodes.reverse();
+                path.edges.reverse();
+                return Ok(Some(path));
+            }
+
+            for (next, edge) in self.why_edges(&node)? {
+                let key = next.key();
+
+                if removed_nodes.contains(&key)
+                    || removed_edges.contains(&(node.key(), key.clone()))
+                    || !visited.insert(key.clone())
+                {
+                    continue;
+                }
+
+                parents.insert(key, (node.clone(), edge));
+                queue.push_back(next);
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn is_why_target(&self, node: &WhyNode, to: &WhyNode) -> bool {
+        match (node, to) {
+            (WhyNode::Package(a), WhyNode::Package(b)) => a.package_name_eq(b),
+            (WhyNode::Component(a), WhyNode::Component(b)) => a == b,
+            (WhyNode::Package(f), WhyNode::Component(name)) => {
+                self.get_owning_component(f).as_ref() == Some(name)
+            }
+            (WhyNode::Component(_), WhyNode::Package(_)) => false,
+        }
+    }
+
+    fn why_edges(&self, node: &WhyNode) -> Result<Vec<(WhyNode, WhyEdge)>, String> {
+        let mut edges: Vec<(WhyNode, WhyEdge)> = Vec::new();
+
+        let mut add = |mut fmri: FMRI, edge: WhyEdge| {
+            fmri.remove_version();
+            edges.push((WhyNode::Package(fmri), edge));
+        };
+
+        match node {
+            WhyNode::Package(fmri) => {
+                let package = match self.get_package_by_fmri(fmri) {
+                    Ok(p) => get!(p),
+                    Err(_) => return Ok(edges),
+                };
+
+                for d in package
+                    .get_effective_version()
+                    .map(|v| v.get_runtime_dependencies().clone())
+                    .unwrap_or_default()
+                {
+                    match &d {
+                        DependTypes::Require(f)
+                        | DependTypes::Optional(f)
+                        | DependTypes::Group(f)
+                        | DependTypes::Conditional(f, _) => {
+                            add(f.clone(), WhyEdge::Runtime(Box::new(d.clone())))
+                        }
+                        DependTypes::RequireAny(l) => {
+                            for f in l.get_ref().iter().cloned() {
+                                add(f, WhyEdge::Runtime(Box::new(d.clone())))
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            WhyNode::Component(name) => {
+                let component = get!(self.get_component_by_name(name)?);
+
+                for p in &component.packages {
+                    add(get!(p.upgrade().unwrap()).fmri.clone(), WhyEdge::Contains);
+                }
+
+                for (dependencies, dependency_type) in [
+                    (component.get_build_dependencies(), Build),
+                    (component.get_test_dependencies(), Test),
+                    (component.get_sys_build_dependencies(), SystemBuild),
+                    (component.get_sys_test_dependencies(), SystemTest),
+                ] {
+                    for p in dependencies {
+                        add(
+                            get!(p.upgrade().unwrap()).fmri.clone(),
+                            WhyEdge::Component(dependency_type.clone()),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(edges)
+    }
+}
@@ -1,20 +1,23 @@
 use std::path::PathBuf;
 
-use fmri::{FMRI, Publisher};
+use fmri::{Publisher, FMRI};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 
+use crate::problems::Problem::SamePackageHasTwoPublishers;
 use crate::{
     packages::{depend_types::DependTypes, dependency_type::DependencyTypes},
     problems::Problem::{
-        MissingComponentForPackage, NonExistingPackageInPkg5, NonExistingRequired,
-        NonExistingRequiredByRenamed, ObsoletedPackageInComponent, ObsoletedRequired,
-        ObsoletedRequiredByRenamed, PackageInMultipleComponents, PartlyObsoletedRequired,
-        PartlyObsoletedRequiredByRenamed, RenamedNeedsRenamed, RenamedPackageInComponent,
-        UnRunnableMakeCommand, UselessComponent,
+        DanglingLink, LicenseMismatch, LinkTargetNotInDependencies, MediatedPathInOneVersion,
+        MediatorConflict, MissingComponentForPackage, MissingLicense, MissingRequiredPackage,
+        NonExistingPackageInPkg5, NonExistingRequired, NonExistingRequiredByRenamed,
+        ObsoleteMediatorDefault, ObsoletedPackageInComponent, ObsoletedRequired,
+        ObsoletedRequiredByRenamed, OwnPackageInRequiredPackages, PackageInMultipleComponents,
+        PartlyObsoletedRequired, PartlyObsoletedRequiredByRenamed, PathConflict,
+        PublisherPolicyViolation, RenameHistoryMismatch, RenameLoop, RenamedNeedsRenamed,
+        RenamedPackageInComponent, RenamedToNonExisting, UnRunnableMakeCommand, UselessComponent,
     },
 };
-use crate::problems::Problem::SamePackageHasTwoPublishers;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub enum Problem {
@@ -33,6 +36,81 @@ pub enum Problem {
     PackageInMultipleComponents(FMRI, Vec<String>),
     NonExistingPackageInPkg5(FMRI, String),
     SamePackageHasTwoPublishers(FMRI, Publisher, Publisher, Option<Publisher>),
+    /// renamed package, its rename target
+    RenamedToNonExisting(FMRI, FMRI),
+    RenameLoop(Vec<FMRI>),
+    /// package, rename target from catalog, rename target from history file, component name
+    RenameHistoryMismatch(FMRI, Option<FMRI>, FMRI, String),
+    /// runtime dependency of component package, component which does not have it in REQUIRED_PACKAGES
+    MissingRequiredPackage(FMRI, String),
+    /// package of component, component which has it in REQUIRED_PACKAGES
+    OwnPackageInRequiredPackages(FMRI, String),
+    /// dependency, its type, package which has it (or none), component which has it (or empty),
+    /// publisher of dependent, publisher of dependency
+    PublisherPolicyViolation(
+        DependTypes,
+        DependencyTypes,
+        FMRI,
+        String,
+        Publisher,
+        Publisher,
+    ),
+    /// path, packages delivering it with their components (or empty) and action types
+    PathConflict(String, Vec<(FMRI, String, String)>),
+    /// package, path of link, its target, component of package (or empty)
+    DanglingLink(FMRI, String, String, String),
+    /// package, path of link, its target, packages delivering target, component of package (or
+    /// empty)
+    LinkTargetNotInDependencies(FMRI, String, String, Vec<FMRI>, String),
+    /// mediator, path, packages delivering links with the same mediator version and different
+    /// targets
+    MediatorConflict(String, String, Vec<FMRI>),
+    /// mediator, version selected by default, obsolete package delivering it
+    ObsoleteMediatorDefault(String, String, FMRI),
+    /// mediator, path, the only version of mediator delivering it, package
+    MediatedPathInOneVersion(String, String, String, FMRI),
+    /// package without license action, its component (or empty)
+    MissingLicense(FMRI, String),
+    /// package, its component, Makefile variable (COMPONENT_LICENSE or COMPONENT_LICENSE_FILE),
+    /// its value, values from license actions of package
+    LicenseMismatch(FMRI, String, String, String, Vec<String>),
+}
+
+impl Problem {
+    /// Returns kind of problem, name of variant in kebab case (e.g. `obsoleted-required`)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MissingComponentForPackage(_) => "missing-component-for-package",
+            RenamedNeedsRenamed(_, _) => "renamed-needs-renamed",
+            RenamedPackageInComponent(_, _) => "renamed-package-in-component",
+            ObsoletedPackageInComponent(_, _) => "obsoleted-package-in-component",
+            UnRunnableMakeCommand(_, _) => "unrunnable-make-command",
+            NonExistingRequired(_, _, _, _) => "non-existing-required",
+            NonExistingRequiredByRenamed(_, _, _) => "non-existing-required-by-renamed",
+            ObsoletedRequired(_, _, _, _) => "obsoleted-required",
+            ObsoletedRequiredByRenamed(_, _, _) => "obsoleted-required-by-renamed",
+            PartlyObsoletedRequired(_, _, _, _) => "partly-obsoleted-required",
+            PartlyObsoletedRequiredByRenamed(_, _, _) => "partly-obsoleted-required-by-renamed",
+            UselessComponent(_) => "useless-component",
+            PackageInMultipleComponents(_, _) => "package-in-multiple-components",
+            NonExistingPackageInPkg5(_, _) => "non-existing-package-in-pkg5",
+            SamePackageHasTwoPublishers(_, _, _, _) => "same-package-has-two-publishers",
+            RenamedToNonExisting(_, _) => "renamed-to-non-existing",
+            RenameLoop(_) => "rename-loop",
+            RenameHistoryMismatch(_, _, _, _) => "rename-history-mismatch",
+            MissingRequiredPackage(_, _) => "missing-required-package",
+            OwnPackageInRequiredPackages(_, _) => "own-package-in-required-packages",
+            PublisherPolicyViolation(_, _, _, _, _, _) => "publisher-policy-violation",
+            PathConflict(_, _) => "path-conflict",
+            DanglingLink(_, _, _, _) => "dangling-link",
+            LinkTargetNotInDependencies(_, _, _, _, _) => "link-target-not-in-dependencies",
+            MediatorConflict(_, _, _) => "mediator-conflict",
+            ObsoleteMediatorDefault(_, _, _) => "obsolete-mediator-default",
+            MediatedPathInOneVersion(_, _, _, _) => "mediated-path-in-one-version",
+            MissingLicense(_, _) => "missing-license",
+            LicenseMismatch(_, _, _, _, _) => "license-mismatch",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -60,16 +138,57 @@ impl Problems {
             | ObsoletedPackageInComponent(f, _)
             | PackageInMultipleComponents(f, _)
             | NonExistingPackageInPkg5(f, _)
-            | SamePackageHasTwoPublishers(f, _, _, _) => {
+            | SamePackageHasTwoPublishers(f, _, _, _)
+            | MissingRequiredPackage(f, _)
+            | OwnPackageInRequiredPackages(f, _)
+            | PublisherPolicyViolation(_, _, f, _, _, _)
+            | DanglingLink(f, _, _, _)
+            | ObsoleteMediatorDefault(_, _, f)
+            | MediatedPathInOneVersion(_, _, _, f)
+            | MissingLicense(f, _)
+            | LicenseMismatch(f, _, _, _, _) => {
                 f.remove_version();
                 f.remove_publisher();
             }
-            RenamedNeedsRenamed(fmri_a, fmri_b) => {
+            RenamedNeedsRenamed(fmri_a, fmri_b) | RenamedToNonExisting(fmri_a, fmri_b) => {
                 fmri_a.remove_version();
                 fmri_b.remove_version();
                 fmri_a.remove_publisher();
                 fmri_b.remove_publisher();
             }
+            RenameLoop(fmris) => {
+                for f in fmris {
+                    f.remove_version();
+                    f.remove_publisher();
+                }
+            }
+            PathConflict(_, deliveries) => {
+                for (f, _, _) in deliveries {
+                    f.remove_version();
+                    f.remove_publisher();
+                }
+            }
+            LinkTargetNotInDependencies(f, _, _, providers, _) => {
+                for f in providers.iter_mut().chain([f]) {
+                    f.remove_version();
+                    f.remove_publisher();
+                }
+            }
+            MediatorConflict(_, _, fmris) => {
+                for f in fmris {
+                    f.remove_version();
+                    f.remove_publisher();
+                }
+            }
+            RenameHistoryMismatch(f, catalog, history, _) => {
+                for f in [Some(f), catalog.as_mut(), Some(history)]
+                    .into_iter()
+                    .flatten()
+                {
+                    f.remove_version();
+                    f.remove_publisher();
+                }
+            }
             UnRunnableMakeCommand(_, _) => {}
             UselessComponent(_) => {}
         }
@@ -79,6 +198,11 @@ impl Problems {
         }
     }
 
+    /// keeps only problems for which `f` returns true
+    pub fn retain<F: FnMut(&Problem) -> bool>(&mut self, f: F) {
+        self.0.retain(f)
+    }
+
     fn contains(&self, problem: &Problem) -> bool {
         let contains_component = |depend_type: &DependTypes,
                                   dependency_type: &DependencyTypes,
@@ -112,20 +236,12 @@ impl Problems {
         };
 
         match problem {
-            NonExistingRequired(depend_type, dependency_type, _, component_name) => {
-                if contains_component(depend_type, dependency_type, component_name) {
-                    return true;
-                }
-            }
-            ObsoletedRequired(depend_type, dependency_type, _, component_name) => {
-                if contains_component(depend_type, dependency_type, component_name) {
-                    return true;
-                }
-            }
-            PartlyObsoletedRequired(depend_type, dependency_type, _, component_name) => {
-                if contains_component(depend_type, dependency_type, component_name) {
-                    return true;
-                }
+            NonExistingRequired(depend_type, dependency_type, _, component_name)
+            | ObsoletedRequired(depend_type, dependency_type, _, component_name)
+            | PartlyObsoletedRequired(depend_type, dependency_type, _, component_name)
+                if contains_component(depend_type, dependency_type, component_name) =>
+            {
+                return true;
             }
             _ => {}
         };
@@ -151,6 +267,20 @@ impl Problems {
                 PackageInMultipleComponents(_, _) => 12,
                 NonExistingPackageInPkg5(_, _) => 13,
                 SamePackageHasTwoPublishers(_, _, _, _) => 14,
+                RenamedToNonExisting(_, _) => 15,
+                RenameLoop(_) => 16,
+                RenameHistoryMismatch(_, _, _, _) => 17,
+                MissingRequiredPackage(_, _) => 18,
+                OwnPackageInRequiredPackages(_, _) => 19,
+                PublisherPolicyViolation(_, _, _, _, _, _) => 20,
+                PathConflict(_, _) => 21,
+                DanglingLink(_, _, _, _) => 22,
+                LinkTargetNotInDependencies(_, _, _, _, _) => 23,
+                MediatorConflict(_, _, _) => 24,
+                ObsoleteMediatorDefault(_, _, _) => 25,
+                MediatedPathInOneVersion(_, _, _, _) => 26,
+                MissingLicense(_, _) => 27,
+                LicenseMismatch(_, _, _, _, _) => 28,
             }
         };
 
@@ -158,7 +288,7 @@ impl Problems {
     }
 
     fn count(&self) {
-        let mut counter: [i16; 15] = [0; 15];
+        let mut counter: [usize; 29] = [0; 29];
         for problem in self.get_ref() {
             match problem {
                 UselessComponent(_) => counter[0] += 1,
@@ -176,6 +306,20 @@ impl Problems {
                 PackageInMultipleComponents(_, _) => counter[12] += 1,
                 NonExistingPackageInPkg5(_, _) => counter[13] += 1,
                 SamePackageHasTwoPublishers(_, _, _, _) => counter[14] += 1,
+                RenamedToNonExisting(_, _) => counter[15] += 1,
+                RenameLoop(_) => counter[16] += 1,
+                RenameHistoryMismatch(_, _, _, _) => counter[17] += 1,
+                MissingRequiredPackage(_, _) => counter[18] += 1,
+                OwnPackageInRequiredPackages(_, _) => counter[19] += 1,
+                PublisherPolicyViolation(_, _, _, _, _, _) => counter[20] += 1,
+                PathConflict(_, _) => counter[21] += 1,
+                DanglingLink(_, _, _, _) => counter[22] += 1,
+                LinkTargetNotInDependencies(_, _, _, _, _) => counter[23] += 1,
+                MediatorConflict(_, _, _) => counter[24] += 1,
+                ObsoleteMediatorDefault(_, _, _) => counter[25] += 1,
+                MediatedPathInOneVersion(_, _, _, _) => counter[26] += 1,
+                MissingLicense(_, _) => counter[27] += 1,
+                LicenseMismatch(_, _, _, _, _) => counter[28] += 1,
             }
         }
 
@@ -196,6 +340,20 @@ impl Problems {
                 12 => error!("Number of packages that are in multiple components: {}", count),
                 13 => error!("Number of packages that are in pkg5 file but do not exist: {}", count),
                 14 => error!("Number of problems with packages that have same publisher: {}", count),
+                15 => error!("Number of renamed packages that are renamed to non existing package: {}", count),
+                16 => error!("Number of rename loops: {}", count),
+                17 => warn!("Number of renames in history files that do not match catalog: {}", count),
+                18 => warn!("Number of runtime dependencies missing in REQUIRED_PACKAGES: {}", count),
+                19 => warn!("Number of components which have their own packages in REQUIRED_PACKAGES: {}", count),
+                20 => error!("Number of dependencies between publishers not allowed by policy: {}", count),
+                21 => error!("Number of paths delivered by multiple packages: {}", count),
+                22 => error!("Number of links whose target is not delivered by any package: {}", count),
+                23 => warn!("Number of links whose target is delivered only by packages which are not dependencies: {}", count),
+                24 => error!("Number of mediated paths with conflicting implementations without priority: {}", count),
+                25 => error!("Number of mediators whose default version is delivered by obsolete package: {}", count),
+                26 => warn!("Number of mediated paths delivered only by one version of mediator: {}", count),
+                27 => error!("Number of packages without license: {}", count),
+                28 => error!("Number of packages whose license does not match component: {}", count),
                 _ => panic!("invalid problem type"),
             }
         }
@@ -211,23 +369,60 @@ impl Problems {
                 | SamePackageHasTwoPublishers(f, _, _, _)
                 | PackageInMultipleComponents(f, _)
                 | MissingComponentForPackage(f)
+                | MissingRequiredPackage(f, _)
+                | OwnPackageInRequiredPackages(f, _)
                 | RenamedPackageInComponent(f, _)
-                | ObsoletedPackageInComponent(f, _) => {
+                | ObsoletedPackageInComponent(f, _)
+                | DanglingLink(f, _, _, _)
+                | ObsoleteMediatorDefault(_, _, f)
+                | MediatedPathInOneVersion(_, _, _, f)
+                | MissingLicense(f, _)
+                | LicenseMismatch(f, _, _, _, _) => {
                     if f.package_name_eq(fmri) {
                         problems.push(problem.clone());
                     }
                 }
-                RenamedNeedsRenamed(f_a, f_b) => {
+                RenamedNeedsRenamed(f_a, f_b) | RenamedToNonExisting(f_a, f_b) => {
                     if f_a.package_name_eq(fmri) || f_b.package_name_eq(fmri) {
                         problems.push(problem.clone());
                     }
                 }
+                RenameLoop(fmris) => {
+                    if fmris.iter().any(|f| f.package_name_eq(fmri)) {
+                        problems.push(problem.clone());
+                    }
+                }
+                PathConflict(_, deliveries) => {
+                    if deliveries.iter().any(|(f, _, _)| f.package_name_eq(fmri)) {
+                        problems.push(problem.clone());
+                    }
+                }
+                MediatorConflict(_, _, fmris) => {
+                    if fmris.iter().any(|f| f.package_name_eq(fmri)) {
+                        problems.push(problem.clone());
+                    }
+                }
+                LinkTargetNotInDependencies(f, _, _, providers, _) => {
+                    if f.package_name_eq(fmri) || providers.iter().any(|p| p.package_name_eq(fmri))
+                    {
+                        problems.push(problem.clone());
+                    }
+                }
+                RenameHistoryMismatch(f, catalog, history, _) => {
+                    if f.package_name_eq(fmri)
+                        || history.package_name_eq(fmri)
+                        || catalog.as_ref().is_some_and(|c| c.package_name_eq(fmri))
+                    {
+                        problems.push(problem.clone());
+                    }
+                }
                 NonExistingRequired(depend_type, _, f, _)
                 | NonExistingRequiredByRenamed(depend_type, _, f)
                 | ObsoletedRequired(depend_type, _, f, _)
                 | ObsoletedRequiredByRenamed(depend_type, _, f)
                 | PartlyObsoletedRequired(depend_type, _, f, _)
-                | PartlyObsoletedRequiredByRenamed(depend_type, _, f) => {
+                | PartlyObsoletedRequiredByRenamed(depend_type, _, f)
+                | PublisherPolicyViolation(depend_type, _, f, _, _, _) => {
                     if f.package_name_eq(fmri) {
                         problems.push(problem.clone());
                         continue;
@@ -266,6 +461,46 @@ impl Problems {
         }
         problems
     }
+
+    pub fn get_problems_related_to_component(&self, component_name: &String) -> Vec<Problem> {
+        self.0
+            .iter()
+            .filter(|problem| match problem {
+                RenamedPackageInComponent(_, c)
+                | ObsoletedPackageInComponent(_, c)
+                | NonExistingRequired(_, _, _, c)
+                | ObsoletedRequired(_, _, _, c)
+                | PartlyObsoletedRequired(_, _, _, c)
+                | UselessComponent(c)
+                | NonExistingPackageInPkg5(_, c)
+                | RenameHistoryMismatch(_, _, _, c)
+                | MissingRequiredPackage(_, c)
+                | OwnPackageInRequiredPackages(_, c)
+                | PublisherPolicyViolation(_, _, _, c, _, _)
+                | DanglingLink(_, _, _, c)
+                | LinkTargetNotInDependencies(_, _, _, _, c)
+                | MissingLicense(_, c)
+                | LicenseMismatch(_, c, _, _, _) => c == component_name,
+                PackageInMultipleComponents(_, cs) => cs.contains(component_name),
+                PathConflict(_, deliveries) => {
+                    deliveries.iter().any(|(_, c, _)| c == component_name)
+                }
+                UnRunnableMakeCommand(_, path) => path.ends_with(component_name),
+                MissingComponentForPackage(_)
+                | RenamedNeedsRenamed(_, _)
+                | NonExistingRequiredByRenamed(_, _, _)
+                | ObsoletedRequiredByRenamed(_, _, _)
+                | PartlyObsoletedRequiredByRenamed(_, _, _)
+                | SamePackageHasTwoPublishers(_, _, _, _)
+                | RenamedToNonExisting(_, _)
+                | RenameLoop(_)
+                | MediatorConflict(_, _, _)
+                | ObsoleteMediatorDefault(_, _, _)
+                | MediatedPathInOneVersion(_, _, _, _) => false,
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 impl Default for Problems {
@@ -309,6 +544,134 @@ pub fn report_problem(problem: &Problem) {
                 components.join(",")
             )
         }
+        RenamedToNonExisting(fmri, renamed_to) => error!(
+            "renamed package {} is renamed to non existing package {}",
+            fmri.get_package_name_as_ref_string(),
+            renamed_to.get_package_name_as_ref_string()
+        ),
+        RenameLoop(fmris) => error!(
+            "rename loop: {} -> {}",
+            fmris
+                .iter()
+                .map(|f| f.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(" -> "),
+            fmris.first().unwrap().get_package_name_as_ref_string()
+        ),
+        RenameHistoryMismatch(fmri, catalog, history, component_name) => warn!(
+            "history file of component {} says package {} is renamed to {}, but in catalog it is {}",
+            component_name,
+            fmri.get_package_name_as_ref_string(),
+            history.get_package_name_as_ref_string(),
+            match catalog {
+                None => "not renamed".to_owned(),
+                Some(c) => format!("renamed to {}", c.get_package_name_as_ref_string()),
+            }
+        ),
+        MissingRequiredPackage(fmri, component_name) => warn!(
+            "package {} is runtime dependency of component {}, but it is not in its REQUIRED_PACKAGES",
+            fmri.get_package_name_as_ref_string(),
+            component_name
+        ),
+        OwnPackageInRequiredPackages(fmri, component_name) => warn!(
+            "component {} has its own package {} in REQUIRED_PACKAGES",
+            component_name,
+            fmri.get_package_name_as_ref_string()
+        ),
+        PublisherPolicyViolation(
+            depend_type,
+            dependency_type,
+            required_by,
+            component_name,
+            from,
+            to,
+        ) => {
+            let (name, fmri) = depend_type.clone().get_name_and_content_as_string();
+
+            match dependency_type {
+                DependencyTypes::Runtime => error!(
+                    "package {} ({}) has {} dependency on {} ({}), which is not allowed by publisher policy",
+                    required_by.get_package_name_as_ref_string(),
+                    from,
+                    name,
+                    fmri,
+                    to
+                ),
+                _ => error!(
+                    "component {} ({}) has {} dependency on {} ({}), which is not allowed by publisher policy",
+                    component_name, from, dependency_type, fmri, to
+                ),
+            }
+        }
+        PathConflict(path, deliveries) => error!(
+            "path {} is delivered by multiple packages: {}",
+            path,
+            deliveries
+                .iter()
+                .map(|(fmri, component_name, action)| match component_name.is_empty() {
+                    true => format!("{} ({})", fmri.get_package_name_as_ref_string(), action),
+                    false => format!(
+                        "{} ({}, component {})",
+                        fmri.get_package_name_as_ref_string(),
+                        action,
+                        component_name
+                    ),
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        DanglingLink(fmri, path, target, _) => error!(
+            "link {} of package {} points to {}, which is not delivered by any package",
+            path,
+            fmri.get_package_name_as_ref_string(),
+            target
+        ),
+        LinkTargetNotInDependencies(fmri, path, target, providers, _) => warn!(
+            "link {} of package {} points to {}, which is delivered only by {} (not in its runtime dependencies)",
+            path,
+            fmri.get_package_name_as_ref_string(),
+            target,
+            providers
+                .iter()
+                .map(|f| f.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        MediatorConflict(mediator, path, fmris) => error!(
+            "mediated link {} (mediator {}) is delivered by {} with the same version and different targets, but without mediator-priority",
+            path,
+            mediator,
+            fmris
+                .iter()
+                .map(|f| f.get_package_name_as_ref_string().clone())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        ObsoleteMediatorDefault(mediator, version, fmri) => error!(
+            "default version {} of mediator {} is delivered by obsolete package {}",
+            version,
+            mediator,
+            fmri.get_package_name_as_ref_string()
+        ),
+        MediatedPathInOneVersion(mediator, path, version, fmri) => warn!(
+            "mediated link {} (mediator {}) is delivered only for version {} (by package {})",
+            path,
+            mediator,
+            version,
+            fmri.get_package_name_as_ref_string()
+        ),
+        MissingLicense(fmri, _) => error!(
+            "package {} does not deliver any license",
+            fmri.get_package_name_as_ref_string()
+        ),
+        LicenseMismatch(fmri, component_name, variable, value, found) => error!(
+            "licenses of package {} ({}) do not match {} of component {} ({})",
+            fmri.get_package_name_as_ref_string(),
+            found.join(", "),
+            variable,
+            component_name,
+            value
+        ),
         UselessComponent(name) => info!("component {} is not needed by any package", name),
         MissingComponentForPackage(fmri) => warn!("missing component for {}", fmri),
         RenamedNeedsRenamed(fmri_a, fmri_b) => error!(
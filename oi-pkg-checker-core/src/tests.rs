@@ -1 +1,116 @@
+mod build_env_test;
+mod build_order_test;
+mod catalog_parts_test;
+mod catalog_signature_test;
+mod catalog_update_test;
+mod closure_test;
+mod dangling_links_test;
 mod de_serialization_test;
+mod fix_test;
+mod history_test;
+mod impact_test;
+mod licenses_test;
+mod manifests_test;
+mod mediators_test;
+mod orphans_test;
+mod p5p_archive_test;
+mod path_conflicts_test;
+mod pkg5_repository_test;
+mod publisher_policy_test;
+mod query_test;
+mod renames_test;
+mod required_packages_test;
+mod runtimes_test;
+mod stats_test;
+mod what_if_test;
+mod why_test;
+
+use fmri::{Publisher, Version, FMRI};
+
+use crate::{
+    assets::manifest::{parse_manifest, Action},
+    packages::package::{Package, PackageVersion},
+    DependTypes,
+};
+
+pub(crate) fn f(name: &str) -> FMRI {
+    FMRI::parse_raw(name).unwrap()
+}
+
+/// Package with one version, e.g. `package("library/foo").renamed_to("library/bar").build()`
+pub(crate) struct TestPackage {
+    fmri: FMRI,
+    version: String,
+    obsolete: bool,
+    renamed: bool,
+    dependencies: Vec<DependTypes>,
+    actions: Vec<Action>,
+}
+
+pub(crate) fn package(name: &str) -> TestPackage {
+    TestPackage {
+        fmri: f(name),
+        version: "1".to_owned(),
+        obsolete: false,
+        renamed: false,
+        dependencies: Vec::new(),
+        actions: Vec::new(),
+    }
+}
+
+impl TestPackage {
+    pub(crate) fn version(mut self, version: &str) -> Self {
+        self.version = version.to_owned();
+        self
+    }
+
+    pub(crate) fn publisher(mut self, publisher: &str) -> Self {
+        self.fmri
+            .change_publisher(Publisher::new(publisher.to_owned()).unwrap());
+        self
+    }
+
+    pub(crate) fn obsolete(mut self, obsolete: bool) -> Self {
+        self.obsolete = obsolete;
+        self
+    }
+
+    pub(crate) fn renamed(mut self, renamed: bool) -> Self {
+        self.renamed = renamed;
+        self
+    }
+
+    /// renamed package with require dependency on `renamed_to`
+    pub(crate) fn renamed_to(self, renamed_to: &str) -> Self {
+        self.renamed(true)
+            .depends(vec![DependTypes::Require(f(renamed_to))])
+    }
+
+    pub(crate) fn depends(mut self, mut dependencies: Vec<DependTypes>) -> Self {
+        self.dependencies.append(&mut dependencies);
+        self
+    }
+
+    /// adds require dependencies
+    pub(crate) fn requires(self, fmris: &[&str]) -> Self {
+        let dependencies = fmris.iter().map(|d| DependTypes::Require(f(d))).collect();
+        self.depends(dependencies)
+    }
+
+    pub(crate) fn manifest(mut self, manifest: &str) -> Self {
+        self.actions = parse_manifest(manifest).unwrap();
+        self
+    }
+
+    pub(crate) fn build(self) -> Package {
+        let mut version = PackageVersion::new(Version::new(self.version).unwrap());
+        version.set_obsolete(self.obsolete);
+        version.set_renamed(self.renamed);
+        version.add_runtime_dependencies(&mut self.dependencies.clone());
+        version.set_actions(self.actions);
+
+        let mut package = Package::new(self.fmri);
+        package.add_package_version(version).unwrap();
+        package
+    }
+}
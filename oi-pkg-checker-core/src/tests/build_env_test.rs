@@ -0,0 +1,53 @@
+use crate::{
+    packages::dependency_type::DependencyTypes::{Build, Test},
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+#[test]
+fn build_env() {
+    let mut components = Components::default();
+    components.add_package(package("pkg://openindiana.org/p").build());
+    components.add_package(
+        package("pkg://openindiana.org/a")
+            .depends(vec![DependTypes::Require(f("e"))])
+            .build(),
+    );
+    components.add_package(package("pkg://encumbered/e").build());
+    components.add_package(package("pkg://openindiana.org/o").obsolete(true).build());
+    components.add_package(
+        package("pkg://openindiana.org/t")
+            .depends(vec![DependTypes::Require(f("p"))])
+            .build(),
+    );
+    components.distribute_reverse_runtime_dependencies();
+
+    let name = "c1".to_owned();
+    components
+        .new_component(name.clone(), vec![f("p")])
+        .unwrap();
+    components
+        .add_repo_dependencies(&name, vec![f("a"), f("o")], &Build)
+        .unwrap();
+    components
+        .add_repo_dependencies(&name, vec![f("t")], &Test)
+        .unwrap();
+
+    let build_env = components.get_build_env(&name, false).unwrap();
+    assert_eq!(
+        build_env.packages,
+        vec![
+            f("pkg://openindiana.org/a"),
+            f("pkg://encumbered/e"),
+            f("pkg://openindiana.org/o")
+        ]
+    );
+    assert_eq!(build_env.publisher, Some("openindiana.org".to_owned()));
+    assert_eq!(build_env.other_publisher, vec![f("pkg://encumbered/e")]);
+    assert_eq!(build_env.obsolete, vec![f("pkg://openindiana.org/o")]);
+    assert!(build_env.own.is_empty());
+
+    let build_env = components.get_build_env(&name, true).unwrap();
+    assert_eq!(build_env.packages.len(), 5);
+    assert_eq!(build_env.own, vec![f("pkg://openindiana.org/p")]);
+}
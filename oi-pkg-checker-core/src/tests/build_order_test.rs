@@ -0,0 +1,62 @@
+use crate::{
+    packages::{
+        cycles::{EdgeType, Or},
+        dependency_type::DependencyTypes::Build,
+    },
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+fn component(components: &mut Components, name: &str, packages: &[&str], build: &[&str]) {
+    components
+        .new_component(name.to_owned(), packages.iter().map(|p| f(p)).collect())
+        .unwrap();
+    components
+        .add_repo_dependencies(
+            &name.to_owned(),
+            build.iter().map(|p| f(p)).collect(),
+            &Build,
+        )
+        .unwrap();
+}
+
+#[test]
+fn build_order() {
+    let mut components = Components::default();
+    components.add_package(package("a").build());
+    components.add_package(
+        package("b")
+            .depends(vec![DependTypes::Require(f("d"))])
+            .build(),
+    );
+    components.add_package(package("c").build());
+    components.add_package(package("d").build());
+    components.add_package(package("e").build());
+    components.add_package(package("f").build());
+    components.distribute_reverse_runtime_dependencies();
+
+    component(&mut components, "c1", &["a"], &[]);
+    component(&mut components, "c2", &["b"], &["a"]);
+    component(&mut components, "c3", &["c"], &["b"]);
+    component(&mut components, "c4", &["d"], &[]);
+    component(&mut components, "c5", &["e"], &["f"]);
+    component(&mut components, "c6", &["f"], &["e"]);
+
+    let graph = components.get_component_build_graph();
+    assert_eq!(graph["c3"].iter().collect::<Vec<_>>(), vec!["c2", "c4"]);
+
+    let build_order = components.get_build_order();
+    assert_eq!(
+        build_order.waves,
+        vec![vec!["c1", "c4", "c5", "c6"], vec!["c2"], vec!["c3"]]
+    );
+    assert_eq!(build_order.cycles.len(), 1);
+    assert_eq!(build_order.cycles[0].components, vec!["c5", "c6"]);
+    assert_eq!(
+        build_order.cycles[0].paths,
+        vec![vec![
+            (Or::Component("c5".to_owned()), EdgeType::Build),
+            (Or::Component("c6".to_owned()), EdgeType::Build)
+        ]]
+    );
+}
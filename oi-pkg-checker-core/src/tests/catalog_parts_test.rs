@@ -0,0 +1,76 @@
+use std::fs;
+
+use crate::{
+    tests::f,
+    {assets::catalogs_c::load_catalog, Components, DependTypes},
+};
+
+const PATH: &str = "/tmp/rust-oi-pkg-checker-core-catalog-parts-test";
+
+fn write(part: &str, content: &str) {
+    fs::write(format!("{}/{}", PATH, part), content).unwrap();
+}
+
+#[test]
+fn catalog_parts() {
+    let _ = fs::remove_dir_all(PATH);
+    fs::create_dir_all(PATH).unwrap();
+
+    write(
+        "catalog.attrs",
+        r#"{"created": "20230101T000000.000000Z", "package-count": 2, "version": 1,
+            "parts": {"catalog.base.C": {}, "catalog.dependency.C": {}, "catalog.summary.C": {}}}"#,
+    );
+    write(
+        "catalog.base.C",
+        r#"{"_SIGNATURE": {"sha-1": "abcd"}, "openindiana.org": {
+            "library/foo": [{"signature-sha-1": "1234", "version": "1.0,5.11-2023.0.0.1:20230101T000000Z"}],
+            "library/bar": [{"signature-sha-1": "5678", "version": "1.0,5.11-2023.0.0.1:20230101T000000Z"}]}}"#,
+    );
+    write(
+        "catalog.dependency.C",
+        r#"{"openindiana.org": {
+            "library/foo": [{"actions": ["depend fmri=library/bar type=require"],
+                "version": "1.0,5.11-2023.0.0.1:20230101T000000Z"}],
+            "library/bar": [{"actions": ["set name=pkg.obsolete value=true"],
+                "version": "1.0,5.11-2023.0.0.1:20230101T000000Z"}]}}"#,
+    );
+    write(
+        "catalog.summary.C",
+        r#"{"openindiana.org": {
+            "library/foo": [{"actions": [
+                "set name=pkg.summary value=\"foo library\"",
+                "set name=pkg.description value=\"foo is library for \\\"foo\\\"\"",
+                "set name=info.classification value=org.opensolaris.category.2008:System/Libraries value=org.opensolaris.category.2008:Development/C",
+                "set name=org.opensolaris.consolidation value=userland"],
+                "version": "1.0,5.11-2023.0.0.1:20230101T000000Z"}]}}"#,
+    );
+
+    let mut components = Components::default();
+    load_catalog(&mut components, PATH.as_ref()).unwrap();
+
+    let foo = get!(components.get_package_by_fmri(&f("library/foo")).unwrap()).clone();
+    let metadata = foo.get_metadata().unwrap();
+    assert_eq!(metadata.summary.as_deref(), Some("foo library"));
+    assert_eq!(
+        metadata.description.as_deref(),
+        Some("foo is library for \"foo\"")
+    );
+    assert_eq!(metadata.classification.len(), 2);
+    assert_eq!(metadata.consolidation.as_deref(), Some("userland"));
+    assert_eq!(
+        foo.get_effective_version()
+            .unwrap()
+            .get_runtime_dependencies(),
+        &vec![DependTypes::Require(f("library/bar"))]
+    );
+
+    let bar = components.get_package_by_fmri(&f("library/bar")).unwrap();
+    assert!(get!(bar).is_obsolete());
+    assert_eq!(get!(bar).get_metadata().unwrap().summary, None);
+
+    write("catalog.attrs", r#"{"version": 2, "parts": {}}"#);
+    assert!(load_catalog(&mut Components::default(), PATH.as_ref()).is_err());
+
+    fs::remove_dir_all(PATH).unwrap();
+}
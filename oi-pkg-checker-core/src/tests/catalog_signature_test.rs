@@ -0,0 +1,73 @@
+use std::fs;
+
+use serde_json::{json, Value};
+
+use crate::assets::catalog_signature::{signature_of, verify_catalog_signatures};
+
+const PATH: &str = "/tmp/rust-oi-pkg-checker-core-catalog-signature-test";
+
+/// Writes part with `_SIGNATURE` and returns its signature
+fn write_signed(name: &str, mut part: Value) -> String {
+    let signature = signature_of(&part).unwrap();
+    part.as_object_mut()
+        .unwrap()
+        .insert("_SIGNATURE".to_owned(), json!({ "sha-1": signature }));
+    fs::write(format!("{}/{}", PATH, name), part.to_string()).unwrap();
+    signature
+}
+
+fn write_catalog(attrs_signature: Option<&str>) {
+    let dependency = write_signed(
+        "catalog.dependency.C",
+        json!({"openindiana.org": {"library/foo": [{"version": "1.0", "actions": []}]}}),
+    );
+    write_signed(
+        "catalog.attrs",
+        json!({
+            "version": 1,
+            "parts": {"catalog.dependency.C": {"signature-sha-1": attrs_signature.unwrap_or(&dependency)}}
+        }),
+    );
+}
+
+/// Part as pkg5 writes it, signature was computed by Python from the part without `_SIGNATURE`:
+/// `hashlib.sha1(json.dumps(part, sort_keys=True, separators=(",", ":")).encode()).hexdigest()`
+const SIGNED_PART: &str = r#"{"_SIGNATURE":{"sha-1":"83388df51957800940a7703d99ad93869ceb0d37"},"openindiana.org":{"library/foo":[{"actions":["set name=pkg.summary value=\"Biblioth\u00e8que f\u00fcr \u03a9mega \ud83e\udd80\""],"version":"1.0,5.11-2023.0.0.1:20230101T000000Z"}]}}"#;
+
+#[test]
+fn catalog_signature() {
+    let _ = fs::remove_dir_all(PATH);
+    fs::create_dir_all(PATH).unwrap();
+
+    write_catalog(None);
+    verify_catalog_signatures(PATH.as_ref()).unwrap();
+    verify_catalog_signatures(format!("{}/catalog.dependency.C", PATH).as_ref()).unwrap();
+
+    // part from other version of catalog
+    write_catalog(Some("0000"));
+    let e = verify_catalog_signatures(PATH.as_ref()).unwrap_err();
+    assert!(e.contains("catalog.dependency.C") && e.contains("catalog.attrs"));
+
+    // corrupted part
+    write_catalog(None);
+    let part = format!("{}/catalog.dependency.C", PATH);
+    fs::write(
+        &part,
+        fs::read_to_string(&part).unwrap().replace("1.0", "2.0"),
+    )
+    .unwrap();
+    let e = verify_catalog_signatures(PATH.as_ref()).unwrap_err();
+    assert!(e.contains("catalog.dependency.C") && e.contains("mismatch"));
+
+    // truncated part
+    let content = fs::read_to_string(&part).unwrap();
+    fs::write(&part, &content[..content.len() / 2]).unwrap();
+    let e = verify_catalog_signatures(part.as_ref()).unwrap_err();
+    assert!(e.contains("catalog.dependency.C"));
+
+    // non-ASCII characters are escaped (characters outside of BMP as surrogate pairs)
+    fs::write(&part, SIGNED_PART).unwrap();
+    verify_catalog_signatures(part.as_ref()).unwrap();
+
+    fs::remove_dir_all(PATH).unwrap();
+}
@@ -0,0 +1,153 @@
+use std::fs;
+
+use crate::{
+    assets::catalogs_c::load_catalog_c,
+    packages::{checks::CheckOptions, dependency_type::DependencyTypes::Build},
+    problems::Problem::{NonExistingRequired, ObsoletedPackageInComponent},
+    tests::f,
+    Components, DependTypes,
+};
+
+const PATH: &str = "/tmp/rust-oi-pkg-checker-core-catalog-update-test";
+const V1: &str = "1.0,5.11-2023.0.0.1:20230101T000000Z";
+const V2: &str = "2.0,5.11-2023.0.0.1:20230201T000000Z";
+
+fn new_data(keep_history: bool) -> Components {
+    fs::write(
+        format!("{}/catalog.dependency.C", PATH),
+        format!(
+            r#"{{"openindiana.org": {{
+                "library/a": [{{"version": "{v}"}}],
+                "library/b": [{{"actions": ["depend fmri=library/a type=require"], "version": "{v}"}}],
+                "library/c": [{{"version": "{v}"}}]}}}}"#,
+            v = V1
+        ),
+    )
+    .unwrap();
+
+    let mut components = Components::default();
+    components.set_keep_history(keep_history);
+    load_catalog_c(
+        &mut components,
+        &format!("{}/catalog.dependency.C", PATH).into(),
+    )
+    .unwrap();
+
+    components
+        .new_component(
+            "c1".to_owned(),
+            vec![f("library/a"), f("library/b"), f("library/c")],
+        )
+        .unwrap();
+    components.new_component("c2".to_owned(), vec![]).unwrap();
+    components
+        .add_repo_dependencies(&"c2".to_owned(), vec![f("library/d")], &Build)
+        .unwrap();
+    components.check_problems().unwrap();
+    components
+}
+
+#[test]
+fn catalog_update() {
+    let _ = fs::remove_dir_all(PATH);
+    fs::create_dir_all(PATH).unwrap();
+
+    let mut components = new_data(true);
+
+    fs::write(
+        format!("{}/catalog.update.20230201T00Z.C", PATH),
+        format!(
+            r#"{{"_SIGNATURE": {{}}, "openindiana.org": {{
+                "library/a": [{{"op-type": "add", "op-time": "20230201T000000.000000Z", "version": "{v2}",
+                    "catalog.dependency.C": {{"actions": ["set name=pkg.obsolete value=true"]}}}}],
+                "library/c": [{{"op-type": "remove", "op-time": "20230201T000000.000000Z", "version": "{v1}"}}],
+                "library/d": [{{"op-type": "add", "op-time": "20230201T000000.000000Z", "version": "{v1}",
+                    "catalog.summary.C": {{"actions": ["set name=pkg.summary value=\"d library\""]}}}}]}}}}"#,
+            v1 = V1,
+            v2 = V2
+        ),
+    )
+    .unwrap();
+    // applied after the first update
+    fs::write(
+        format!("{}/catalog.update.20230202T00Z.C", PATH),
+        format!(
+            r#"{{"openindiana.org": {{
+                "library/b": [{{"op-type": "add", "op-time": "20230202T000000.000000Z", "version": "{v2}",
+                    "catalog.dependency.C": {{"actions": ["depend fmri=library/d type=require"]}}}}]}}}}"#,
+            v2 = V2
+        ),
+    )
+    .unwrap();
+
+    let diff = components
+        .apply_catalog_updates(
+            &[
+                format!("{}/catalog.update.20230202T00Z.C", PATH).into(),
+                format!("{}/catalog.update.20230201T00Z.C", PATH).into(),
+            ],
+            &CheckOptions::default(),
+        )
+        .unwrap();
+
+    assert!(components.get_package_by_fmri(&f("library/c")).is_err());
+    assert!(get!(components.get_package_by_fmri(&f("library/a")).unwrap()).is_obsolete());
+
+    let d = components.get_package_by_fmri(&f("library/d")).unwrap();
+    assert_eq!(
+        get!(d).get_metadata().unwrap().summary.as_deref(),
+        Some("d library")
+    );
+    assert_eq!(get!(d).get_runtime_dependents().len(), 1);
+    assert_eq!(get!(d).get_git_dependents(Build).unwrap().len(), 1);
+    assert_eq!(
+        get!(components.get_package_by_fmri(&f("library/b")).unwrap())
+            .get_effective_version()
+            .unwrap()
+            .get_runtime_dependencies(),
+        &vec![DependTypes::Require(f("library/d"))]
+    );
+    assert!(
+        get!(components.get_package_by_fmri(&f("library/a")).unwrap())
+            .get_runtime_dependents()
+            .is_empty()
+    );
+
+    assert!(diff.added.contains(&ObsoletedPackageInComponent(
+        f("library/a"),
+        "c1".to_owned()
+    )));
+    assert!(diff.resolved.iter().any(|p| matches!(
+        p,
+        NonExistingRequired(_, Build, _, c) if c == "c2"
+    )));
+
+    // removing obsolete version makes package non-obsolete again
+    fs::write(
+        format!("{}/catalog.update.20230203T00Z.C", PATH),
+        format!(
+            r#"{{"openindiana.org": {{
+                "library/a": [{{"op-type": "remove", "op-time": "20230203T000000.000000Z", "version": "{v2}"}}]}}}}"#,
+            v2 = V2
+        ),
+    )
+    .unwrap();
+    components
+        .apply_catalog_updates(
+            &[format!("{}/catalog.update.20230203T00Z.C", PATH).into()],
+            &CheckOptions::default(),
+        )
+        .unwrap();
+    assert!(!get!(components.get_package_by_fmri(&f("library/a")).unwrap()).is_obsolete());
+
+    // without history only effective version is known
+    let mut components = new_data(false);
+    assert!(components
+        .apply_catalog_updates(
+            &[format!("{}/catalog.update.20230201T00Z.C", PATH).into()],
+            &CheckOptions::default(),
+        )
+        .is_err());
+
+    fs::remove_dir_all(PATH).unwrap();
+}
@@ -0,0 +1,60 @@
+use fmri::fmri_list::FMRIList;
+
+use crate::{
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+#[test]
+fn install_closure() {
+    let mut components = Components::default();
+    components.add_package(
+        package("meta")
+            .depends(vec![
+                DependTypes::Require(f("a")),
+                DependTypes::Group(f("g")),
+                DependTypes::RequireAny(FMRIList::from(vec![f("obs"), f("missing"), f("x")])),
+                DependTypes::Conditional(f("c"), f("a")),
+                DependTypes::Conditional(f("d"), f("z")),
+                DependTypes::Optional(f("d")),
+            ])
+            .build(),
+    );
+    components.add_package(
+        package("a")
+            .depends(vec![DependTypes::Require(f("n"))])
+            .build(),
+    );
+    components.add_package(package("g").build());
+    components.add_package(package("obs").obsolete(true).build());
+    components.add_package(
+        package("x")
+            .renamed(true)
+            .depends(vec![DependTypes::Require(f("y"))])
+            .build(),
+    );
+    components.add_package(
+        package("y")
+            .depends(vec![DependTypes::Require(f("obs"))])
+            .build(),
+    );
+    components.add_package(package("c").build());
+    components.add_package(package("d").build());
+    components.add_package(package("z").build());
+    components.distribute_reverse_runtime_dependencies();
+
+    let closure = components.get_install_closure(&[f("meta")]).unwrap();
+
+    assert_eq!(
+        closure.packages,
+        vec![f("a"), f("c"), f("g"), f("meta"), f("obs"), f("x"), f("y")]
+    );
+    assert_eq!(closure.obsolete, vec![f("obs")]);
+    assert_eq!(closure.renamed, vec![f("x")]);
+    assert_eq!(
+        closure.unresolvable,
+        vec![(f("a"), DependTypes::Require(f("n")))]
+    );
+
+    assert!(components.get_install_closure(&[f("n")]).is_err());
+}
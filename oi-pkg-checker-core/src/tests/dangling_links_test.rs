@@ -0,0 +1,98 @@
+use crate::{
+    problems::Problem::{DanglingLink, LinkTargetNotInDependencies},
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+#[test]
+fn dangling_links() {
+    let mut components = Components::default();
+    components.set_keep_actions(true);
+    components.add_package(
+        package("library/a")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .depends(vec![DependTypes::Require(f("library/b"))])
+            .manifest(
+                "file aaaa path=usr/lib/liba.so.1\n\
+         link path=usr/lib/liba.so target=liba.so.1\n\
+         link path=usr/lib/libb.so target=../lib/./libb.so.1\n\
+         link path=usr/bin/c target=/usr/lib/64/c\n\
+         hardlink path=usr/bin/d target=../lib/d\n\
+         link path=usr/bin/e target=../lib/e variant.arch=sparc\n\
+         link path=usr/bin/loop target=loop/x\n\
+         link path=usr/bin/f target=../lib/arch/c variant.arch=i386\n",
+            )
+            .build(),
+    );
+    components.add_package(
+        package("library/b")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .manifest(
+                "file bbbb path=usr/lib/libb.so.1\n\
+         file eeee path=usr/lib/e variant.arch=i386\n",
+            )
+            .build(),
+    );
+    components.add_package(
+        package("library/c")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .manifest(
+                "dir path=usr/lib/amd64\n\
+         link path=usr/lib/64 target=amd64\n\
+         dir path=usr/lib/sparcv9 variant.arch=sparc\n\
+         link path=usr/lib/arch target=sparcv9 variant.arch=sparc\n\
+         link path=usr/lib/arch target=amd64 variant.arch=i386\n\
+         file cccc path=usr/lib/amd64/c\n\
+         file dddd path=usr/lib/d\n",
+            )
+            .build(),
+    );
+    // manifest of library/h is not loaded
+    components.add_package(
+        package("library/g")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .depends(vec![DependTypes::Require(f("library/h"))])
+            .manifest("link path=usr/bin/g target=../lib/libh.so.1\n")
+            .build(),
+    );
+    components.add_package(
+        package("library/h")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .build(),
+    );
+    components
+        .new_component("a".to_owned(), vec![f("library/a")])
+        .unwrap();
+
+    components.check_dangling_links().unwrap();
+
+    let problems = components.problems.get_ref();
+    assert_eq!(problems.len(), 5);
+    for (path, target) in [("usr/bin/e", "../lib/e"), ("usr/bin/loop", "loop/x")] {
+        assert!(problems.contains(&DanglingLink(
+            f("library/a"),
+            path.to_owned(),
+            target.to_owned(),
+            "a".to_owned()
+        )));
+    }
+    // link in directory of target is followed only for the same variant
+    for (path, target) in [
+        ("usr/bin/c", "/usr/lib/64/c"),
+        ("usr/bin/d", "../lib/d"),
+        ("usr/bin/f", "../lib/arch/c"),
+    ] {
+        assert!(problems.contains(&LinkTargetNotInDependencies(
+            f("library/a"),
+            path.to_owned(),
+            target.to_owned(),
+            vec![f("library/c")],
+            "a".to_owned()
+        )));
+    }
+}
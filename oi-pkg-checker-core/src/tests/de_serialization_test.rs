@@ -12,6 +12,17 @@ use crate::{
 
 const PATH: &str = "/tmp/rust-oi-pkg-checker-core-de-serialization-test.bin";
 
+#[test]
+fn old_data() {
+    let path = format!("{}.old", PATH);
+    // length of components from data without format version
+    fs::write(&path, [3, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+    assert!(Components::deserialize(&path)
+        .unwrap_err()
+        .contains("re-run `run`"));
+    fs::remove_file(&path).unwrap();
+}
+
 #[test]
 fn serialization() {
     new_data().serialize(PATH).unwrap();
@@ -48,7 +59,8 @@ fn new_valid_data() -> Components {
     let p = |f: FMRI,
              versions: Vec<PackageVersion>,
              obsolete: bool,
-             renamed: bool|
+             renamed: bool,
+             renamed_to: Option<FMRI>|
      -> shared_type!(Package) {
         new!(Package {
             fmri: f,
@@ -56,6 +68,7 @@ fn new_valid_data() -> Components {
             component: None,
             obsolete,
             renamed,
+            renamed_to,
             runtime_dependents: vec![],
             build_dependents: vec![],
             test_dependents: vec![],
@@ -83,10 +96,16 @@ fn new_valid_data() -> Components {
     p1_v1.set_renamed(true);
     p2_v1.set_obsolete(true);
     p2_v2.set_renamed(true);
-    let p1 = p(p1_fmri.clone(), vec![p1_v1, p1_v2], true, false);
-    let p2 = p(p2_fmri.clone(), vec![p2_v1, p2_v2], false, true);
-    let p3 = p(p3_fmri.clone(), vec![p3_v1, p3_v2], false, false);
-    let p4 = p(p4_fmri.clone(), vec![p4_v1, p4_v2], false, false);
+    let p1 = p(p1_fmri.clone(), vec![p1_v1, p1_v2], true, false, None);
+    let p2 = p(
+        p2_fmri.clone(),
+        vec![p2_v1, p2_v2],
+        false,
+        true,
+        Some(p4_fmri.clone()),
+    );
+    let p3 = p(p3_fmri.clone(), vec![p3_v1, p3_v2], false, false, None);
+    let p4 = p(p4_fmri.clone(), vec![p4_v1, p4_v2], false, false, None);
     let mut components = Components::default();
     components.packages.push(clone!(&p1));
     components.packages.push(clone!(&p2));
@@ -121,6 +140,8 @@ fn new_valid_data() -> Components {
             test: deps.clone().iter().map(f).collect(),
             sys_build: deps.clone().iter().map(f).collect(),
             sys_test: deps.clone().iter().map(f).collect(),
+            license: None,
+            license_file: None,
         })
     };
 
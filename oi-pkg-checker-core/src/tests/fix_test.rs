@@ -0,0 +1,123 @@
+use std::fs;
+
+use crate::{
+    packages::{dependency_type::DependencyTypes, fix::Fix},
+    tests::{f, package},
+    Components,
+};
+
+const PATH: &str = "/tmp/rust-oi-pkg-checker-core-fix-test/components";
+
+fn new_data() -> Components {
+    let mut components = Components::default();
+    components.add_package(package("library/obs").version("1-1").obsolete(true).build());
+    components.add_package(
+        package("library/old")
+            .version("1-1")
+            .renamed_to("library/new")
+            .build(),
+    );
+    components.add_package(package("library/new").version("2-1").build());
+    components.add_package(
+        package("library/x")
+            .version("1-1")
+            .renamed_to("library/old")
+            .build(),
+    );
+    components.add_package(
+        package("library/r")
+            .version("1-1")
+            .renamed_to("library/new")
+            .build(),
+    );
+    components.add_package(package("system/libc").version("1-1").build());
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("c1".to_owned(), vec![f("library/r"), f("library/new")])
+        .unwrap();
+    components
+        .add_repo_dependencies(
+            &"c1".to_owned(),
+            vec![f("library/obs"), f("library/old"), f("system/libc")],
+            &DependencyTypes::Build,
+        )
+        .unwrap();
+
+    components.check_problems().unwrap();
+    components
+}
+
+#[test]
+fn fixes() {
+    let components = new_data();
+    let fixes = components.get_fixes();
+
+    assert!(fixes.contains(&Fix::RemoveDependency {
+        component: "c1".to_owned(),
+        dependency_type: DependencyTypes::Build,
+        fmri: f("library/obs"),
+    }));
+    assert!(fixes.contains(&Fix::ReplaceDependency {
+        component: "c1".to_owned(),
+        dependency_type: DependencyTypes::Build,
+        fmri: f("library/old"),
+        replacement: f("library/new"),
+    }));
+    assert!(fixes.contains(&Fix::ReplaceRenameTarget {
+        fmri: f("library/x"),
+        renamed_to: f("library/old"),
+        replacement: f("library/new"),
+    }));
+
+    fs::create_dir_all(format!("{}/c1", PATH)).unwrap();
+    fs::write(
+        format!("{}/c1/Makefile", PATH),
+        "COMPONENT_NAME= c1\n\
+        \n\
+        REQUIRED_PACKAGES += library/obs\n\
+        REQUIRED_PACKAGES += library/old\n\
+        REQUIRED_PACKAGES += system/libc\n",
+    )
+    .unwrap();
+    fs::write(
+        format!("{}/c1/history", PATH),
+        "library/x@1-1 library/old@1-1\n",
+    )
+    .unwrap();
+
+    let patches = components.create_patches(&fixes, PATH.as_ref()).unwrap();
+
+    assert_eq!(patches.len(), 2);
+    assert_eq!(
+        patches[0].diff(),
+        "--- a/components/c1/Makefile\n\
+        +++ b/components/c1/Makefile\n\
+        @@ -1,5 +1,4 @@\n \
+        COMPONENT_NAME= c1\n \
+        \n\
+        -REQUIRED_PACKAGES += library/obs\n\
+        -REQUIRED_PACKAGES += library/old\n\
+        +REQUIRED_PACKAGES += library/new\n \
+        REQUIRED_PACKAGES += system/libc\n"
+    );
+    assert_eq!(
+        patches[1].diff(),
+        "--- a/components/c1/history\n\
+        +++ b/components/c1/history\n\
+        @@ -1,1 +1,1 @@\n\
+        -library/x@1-1 library/old@1-1\n\
+        +library/x@1-1 library/new@2-1\n"
+    );
+
+    patches[0].apply().unwrap();
+    assert_eq!(
+        fs::read_to_string(format!("{}/c1/Makefile", PATH)).unwrap(),
+        "COMPONENT_NAME= c1\n\
+        \n\
+        REQUIRED_PACKAGES += library/new\n\
+        REQUIRED_PACKAGES += system/libc\n"
+    );
+
+    fs::remove_dir_all("/tmp/rust-oi-pkg-checker-core-fix-test").unwrap();
+}
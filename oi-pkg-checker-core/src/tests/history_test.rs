@@ -0,0 +1,70 @@
+use fmri::{Version, FMRI};
+
+use crate::{
+    packages::{
+        history::Transition,
+        package::{Package, PackageVersion},
+    },
+    DependTypes,
+};
+
+fn new_package() -> Package {
+    let a = FMRI::parse_raw("a").unwrap();
+    let b = FMRI::parse_raw("b").unwrap();
+
+    let mut v1 = PackageVersion::new(Version::new("1-1:20200101T000000Z".to_owned()).unwrap());
+    let mut v2 = PackageVersion::new(Version::new("2-1:20210101T000000Z".to_owned()).unwrap());
+    let mut v3 = PackageVersion::new(Version::new("3-1:20220101T000000Z".to_owned()).unwrap());
+    v1.add_runtime_dependencies(&mut vec![DependTypes::Require(a.clone())]);
+    v2.add_runtime_dependencies(&mut vec![
+        DependTypes::Require(FMRI::parse_raw("a@2").unwrap()),
+        DependTypes::Require(b.clone()),
+    ]);
+    v3.set_obsolete(true);
+
+    let mut package = Package::new(FMRI::parse_raw("p").unwrap());
+    package.add_package_version(v2).unwrap();
+    package.add_package_version(v1).unwrap();
+    package.add_package_version(v3).unwrap();
+    package
+}
+
+#[test]
+fn effective_version() {
+    let package = new_package();
+
+    assert_eq!(
+        package
+            .get_effective_version()
+            .unwrap()
+            .get_version()
+            .to_string(),
+        "@2-1:20210101T000000Z"
+    );
+}
+
+#[test]
+fn history() {
+    let history = new_package().get_history();
+
+    assert_eq!(history.len(), 3);
+
+    assert_eq!(history[0].timestamp, Some("20200101T000000Z".to_owned()));
+    assert_eq!(history[0].transition, None);
+    assert_eq!(
+        history[0].added,
+        vec![DependTypes::Require(FMRI::parse_raw("a").unwrap())]
+    );
+
+    // raising version of "a" is not a change
+    assert_eq!(history[1].transition, None);
+    assert_eq!(
+        history[1].added,
+        vec![DependTypes::Require(FMRI::parse_raw("b").unwrap())]
+    );
+    assert!(history[1].removed.is_empty());
+
+    assert_eq!(history[2].transition, Some(Transition::Obsoleted));
+    assert!(history[2].added.is_empty());
+    assert_eq!(history[2].removed.len(), 2);
+}
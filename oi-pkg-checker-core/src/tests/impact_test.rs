@@ -0,0 +1,91 @@
+use crate::{
+    packages::{checks::CheckOptions, dependency_type::DependencyTypes, impact::ImpactKind},
+    problems::Problem::ObsoletedRequired,
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+fn new_data() -> Components {
+    let mut components = Components::default();
+    components.add_package(package("library/a").build());
+    components.add_package(
+        package("library/b")
+            .depends(vec![DependTypes::Require(f("library/a"))])
+            .build(),
+    );
+    components.add_package(
+        package("library/c")
+            .depends(vec![DependTypes::Require(f("library/b"))])
+            .build(),
+    );
+    components.add_package(
+        package("library/d")
+            .depends(vec![DependTypes::Optional(f("library/b"))])
+            .build(),
+    );
+    components.add_package(package("library/e").build());
+    components.add_package(
+        package("library/i")
+            .depends(vec![DependTypes::Incorporate(f("library/a"))])
+            .build(),
+    );
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("c1".to_owned(), vec![f("library/c")])
+        .unwrap();
+    components
+        .new_component("c2".to_owned(), vec![f("library/e")])
+        .unwrap();
+    components
+        .add_repo_dependencies(
+            &"c2".to_owned(),
+            vec![f("library/a")],
+            &DependencyTypes::Build,
+        )
+        .unwrap();
+
+    components
+}
+
+#[test]
+fn impact() {
+    let components = new_data();
+    let impact = components
+        .get_impact(&[f("library/a")], &CheckOptions::default())
+        .unwrap();
+
+    assert_eq!(impact.components.len(), 2);
+    assert_eq!(impact.components[0].name, "c1");
+    assert_eq!(
+        impact.components[0].packages,
+        vec![(f("library/c"), ImpactKind::Require)]
+    );
+    assert!(impact.components[0].dependency_types.is_empty());
+    assert_eq!(impact.components[1].name, "c2");
+    assert_eq!(
+        impact.components[1].packages,
+        vec![(f("library/e"), ImpactKind::Build)]
+    );
+    assert_eq!(
+        impact.components[1].dependency_types,
+        vec![DependencyTypes::Build]
+    );
+
+    assert_eq!(
+        impact.packages,
+        vec![
+            (f("library/b"), ImpactKind::Require),
+            (f("library/d"), ImpactKind::Soft)
+        ]
+    );
+
+    assert!(impact.problems.iter().any(|p| matches!(
+        p,
+        ObsoletedRequired(DependTypes::Require(a), DependencyTypes::Build, _, c)
+            if a.package_name_eq(&f("library/a")) && c == "c2"
+    )));
+
+    // original data must stay untouched
+    assert!(!crate::get!(components.get_package_by_fmri(&f("library/a")).unwrap()).is_obsolete());
+}
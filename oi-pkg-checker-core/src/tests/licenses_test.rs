@@ -0,0 +1,68 @@
+use crate::{
+    problems::Problem::{LicenseMismatch, MissingLicense},
+    tests::{f, package},
+    Components,
+};
+
+#[test]
+fn licenses() {
+    let mut components = Components::default();
+    components.set_keep_actions(true);
+    for (name, manifest, obsolete) in [
+        ("library/ok", "license foo.license license=MIT\n", false),
+        ("library/none", "file path=usr/lib/none\n", false),
+        ("library/wrong", "license COPYING license=GPLv2\n", false),
+        (
+            "library/hashed",
+            "license 0123456789abcdef0123456789abcdef01234567 license=MIT\n",
+            false,
+        ),
+        // manifest is not loaded
+        ("library/unknown", "", false),
+        (
+            "library/obsolete",
+            "set name=pkg.obsolete value=true\n",
+            true,
+        ),
+    ] {
+        components.add_package(
+            package(name)
+                .version("1.0")
+                .publisher("openindiana.org")
+                .manifest(manifest)
+                .obsolete(obsolete)
+                .build(),
+        );
+    }
+    components
+        .new_component(
+            "c".to_owned(),
+            ["ok", "none", "wrong", "hashed", "unknown"]
+                .iter()
+                .map(|n| f(&format!("library/{}", n)))
+                .collect(),
+        )
+        .unwrap();
+    get_mut!(components.get_component_by_name(&"c".to_owned()).unwrap())
+        .set_license(Some("MIT".to_owned()), Some("foo.license".to_owned()));
+
+    components.check_licenses().unwrap();
+
+    let problems = components.problems.get_ref();
+    assert_eq!(problems.len(), 3);
+    assert!(problems.contains(&MissingLicense(f("library/none"), "c".to_owned())));
+    assert!(problems.contains(&LicenseMismatch(
+        f("library/wrong"),
+        "c".to_owned(),
+        "COMPONENT_LICENSE".to_owned(),
+        "MIT".to_owned(),
+        vec!["GPLv2".to_owned()]
+    )));
+    assert!(problems.contains(&LicenseMismatch(
+        f("library/wrong"),
+        "c".to_owned(),
+        "COMPONENT_LICENSE_FILE".to_owned(),
+        "foo.license".to_owned(),
+        vec!["COPYING".to_owned()]
+    )));
+}
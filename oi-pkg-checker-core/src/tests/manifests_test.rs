@@ -0,0 +1,116 @@
+use std::fs;
+
+use fmri::Version;
+
+use crate::{
+    assets::{
+        open_indiana_oi_userland_git::load_component_manifests,
+        pkg5_repository::{encode, load_repository},
+    },
+    packages::package::{Package, PackageVersion},
+    tests::f,
+    Components,
+};
+
+const PATH: &str = "/tmp/rust-oi-pkg-checker-core-manifests-test";
+const VERSION: &str = "1.0,5.11-2023.0.0.1:20230101T000000Z";
+
+fn action_names(components: &Components, name: &str) -> Vec<String> {
+    get!(components.get_package_by_fmri(&f(name)).unwrap())
+        .get_effective_version()
+        .unwrap()
+        .get_actions()
+        .iter()
+        .map(|a| a.name.clone())
+        .collect()
+}
+
+#[test]
+fn repository_manifests() {
+    let path = format!("{}/repo", PATH);
+    let _ = fs::remove_dir_all(&path);
+    let pkg_path = format!(
+        "{}/publisher/openindiana.org/pkg/{}",
+        path,
+        encode("web/foo")
+    );
+    fs::create_dir_all(&pkg_path).unwrap();
+    fs::write(
+        format!("{}/pkg5.repository", path),
+        "[repository]\nversion = 4\n",
+    )
+    .unwrap();
+    fs::write(
+        format!("{}/{}", pkg_path, encode(VERSION)),
+        "set name=pkg.fmri value=pkg://openindiana.org/web/foo@1.0\n\
+         dir path=usr/bin owner=root group=bin mode=0755\n\
+         file 1234 path=usr/bin/foo owner=root group=bin mode=0555\n\
+         link path=usr/bin/foo2 target=foo\n\
+         user username=foo uid=100 group=other\n\
+         license 5678 license=MIT\n",
+    )
+    .unwrap();
+
+    let mut components = Components::default();
+    load_repository(&mut components, path.as_ref()).unwrap();
+    assert!(action_names(&components, "web/foo").is_empty());
+
+    let mut components = Components::default();
+    components.set_keep_actions(true);
+    load_repository(&mut components, path.as_ref()).unwrap();
+    assert_eq!(
+        action_names(&components, "web/foo"),
+        vec!["set", "dir", "file", "link", "user", "license"]
+    );
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+#[test]
+fn component_manifests() {
+    let path = format!("{}/components", PATH);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(format!("{}/foo/build", path)).unwrap();
+    fs::write(
+        format!("{}/foo/build/manifest-i386-foo.published", path),
+        "set name=pkg.fmri value=pkg:/library/foo@1.0,5.11-2023.0.0.1\n\
+         file path=usr/lib/libfoo.so.1 owner=root group=bin mode=0555\n\
+         hardlink path=usr/lib/libfoo.so target=libfoo.so.1\n",
+    )
+    .unwrap();
+    // built version is not in catalog
+    fs::write(
+        format!("{}/foo/build/manifest-sparc-foo.published", path),
+        "set name=pkg.fmri value=pkg:/library/foo@1.1,5.11-2023.0.0.2\n\
+         file path=usr/lib/libfoo.so.2 owner=root group=bin mode=0555\n",
+    )
+    .unwrap();
+    // not published manifest
+    fs::write(
+        format!("{}/foo/build/manifest-i386-foo.mogrified", path),
+        "file path=usr/lib/other owner=root group=bin mode=0555\n",
+    )
+    .unwrap();
+
+    let mut package = Package::new(f("pkg://openindiana.org/library/foo"));
+    package
+        .add_package_version(PackageVersion::new(
+            Version::new(VERSION.to_owned()).unwrap(),
+        ))
+        .unwrap();
+
+    let mut components = Components::default();
+    components.add_package(package);
+    components
+        .new_component("foo".to_owned(), vec![f("library/foo")])
+        .unwrap();
+    components.new_component("bar".to_owned(), vec![]).unwrap();
+
+    load_component_manifests(&mut components, path.as_ref()).unwrap();
+    assert_eq!(
+        action_names(&components, "library/foo"),
+        vec!["set", "file", "hardlink"]
+    );
+
+    fs::remove_dir_all(&path).unwrap();
+}
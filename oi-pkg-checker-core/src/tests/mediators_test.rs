@@ -0,0 +1,110 @@
+use crate::{
+    problems::Problem::{MediatedPathInOneVersion, MediatorConflict, ObsoleteMediatorDefault},
+    tests::{f, package},
+    Components,
+};
+
+#[test]
+fn mediators() {
+    let mut components = Components::default();
+    components.set_keep_actions(true);
+    for (name, manifest, obsolete) in [
+        (
+            "runtime/python-39",
+            "link path=usr/bin/python target=python3.9 mediator=python mediator-version=3.9\n\
+             link path=usr/bin/pydoc target=pydoc3.9 mediator=python mediator-version=3.9\n",
+            false,
+        ),
+        (
+            "runtime/python-310",
+            "link path=usr/bin/python target=python3.10 mediator=python mediator-version=3.10\n",
+            false,
+        ),
+        (
+            "runtime/python-311",
+            "link path=usr/bin/python target=python3.11 mediator=python mediator-version=3.11\n",
+            true,
+        ),
+        (
+            "runtime/java/a",
+            "link path=usr/bin/java target=../jdk-a/bin/java mediator=java mediator-version=11\n",
+            false,
+        ),
+        (
+            "runtime/java/b",
+            "link path=usr/bin/java target=../jdk-b/bin/java mediator=java mediator-version=11\n",
+            false,
+        ),
+        (
+            "runtime/java/c",
+            "link path=usr/bin/java target=../jdk-c/bin/java mediator=java mediator-version=17 \
+             mediator-priority=vendor\n",
+            false,
+        ),
+        (
+            "runtime/java/d",
+            "link path=usr/bin/java target=../jdk-d/bin/java mediator=java mediator-version=17 \
+             mediator-priority=vendor\n",
+            false,
+        ),
+        (
+            "runtime/java/e",
+            "link path=usr/bin/java target=../jdk-e/bin/java mediator=java mediator-version=21 \
+             mediator-priority=vendor\n",
+            false,
+        ),
+        (
+            "runtime/java/f",
+            "link path=usr/bin/java target=../jdk-f/bin/java mediator=java mediator-version=21\n",
+            false,
+        ),
+        (
+            "developer/gcc/a",
+            "link path=usr/bin/gcc target=gcc-a mediator=gcc mediator-version=10 \
+             variant.arch=i386\n",
+            false,
+        ),
+        (
+            "developer/gcc/b",
+            "link path=usr/bin/gcc target=gcc-b mediator=gcc mediator-version=10 \
+             variant.arch=sparc\n",
+            false,
+        ),
+    ] {
+        components.add_package(
+            package(name)
+                .version("1.0")
+                .publisher("openindiana.org")
+                .manifest(manifest)
+                .obsolete(obsolete)
+                .build(),
+        );
+    }
+
+    components.check_mediators().unwrap();
+
+    let problems = components.problems.get_ref();
+    assert_eq!(problems.len(), 4);
+    assert!(problems.contains(&MediatorConflict(
+        "java".to_owned(),
+        "usr/bin/java".to_owned(),
+        vec![f("runtime/java/a"), f("runtime/java/b")]
+    )));
+    // the same priority does not select one of the links
+    assert!(problems.contains(&MediatorConflict(
+        "java".to_owned(),
+        "usr/bin/java".to_owned(),
+        vec![f("runtime/java/c"), f("runtime/java/d")]
+    )));
+    assert!(problems.contains(&ObsoleteMediatorDefault(
+        "python".to_owned(),
+        "3.11".to_owned(),
+        f("runtime/python-311")
+    )));
+    assert!(problems.contains(&MediatedPathInOneVersion(
+        "python".to_owned(),
+        "usr/bin/pydoc".to_owned(),
+        "3.9".to_owned(),
+        f("runtime/python-39")
+    )));
+}
@@ -0,0 +1,53 @@
+use crate::{
+    packages::dependency_type::DependencyTypes::Test,
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+#[test]
+fn orphans() {
+    let mut components = Components::default();
+    components.add_package(
+        package("metapackages/base")
+            .depends(vec![DependTypes::Group(f("editor/vim"))])
+            .build(),
+    );
+    components.add_package(
+        package("incorporation")
+            .depends(vec![
+                DependTypes::Incorporate(f("library/dead")),
+                DependTypes::Incorporate(f("editor/vim")),
+            ])
+            .build(),
+    );
+    components.add_package(
+        package("editor/vim")
+            .depends(vec![DependTypes::Require(f("library/ncurses"))])
+            .build(),
+    );
+    components.add_package(package("library/ncurses").build());
+    components.add_package(package("library/dead").build());
+    components.add_package(package("library/tested").build());
+    components.add_package(package("library/old").obsolete(true).build());
+    components.add_package(
+        package("tool")
+            .depends(vec![DependTypes::Require(f("tool"))])
+            .build(),
+    );
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("tool".to_owned(), vec![f("tool")])
+        .unwrap();
+    components
+        .add_repo_dependencies(&"tool".to_owned(), vec![f("library/tested")], &Test)
+        .unwrap();
+
+    let orphans = components.get_orphans().unwrap();
+
+    assert_eq!(
+        orphans.applications,
+        vec![(f("tool"), Some("tool".to_owned()))]
+    );
+    assert_eq!(orphans.libraries, vec![(f("library/dead"), None)]);
+}
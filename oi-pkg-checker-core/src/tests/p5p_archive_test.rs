@@ -0,0 +1,172 @@
+use std::{fs, process::Command};
+
+use crate::{
+    assets::{p5p_archive::load_archive, pkg5_repository::encode},
+    packages::{
+        checks::CheckOptions,
+        dependency_type::DependencyTypes::{Build, Runtime},
+    },
+    problems::Problem::{
+        NonExistingPackageInPkg5, NonExistingRequired, ObsoletedPackageInComponent,
+        PartlyObsoletedRequired,
+    },
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+const PATH: &str = "/tmp/rust-oi-pkg-checker-core-p5p-archive-test";
+const V1: &str = "1.0,5.11-2023.0.0.1:20230101T000000Z";
+const V2: &str = "2.0,5.11-2023.0.0.1:20230201T000000Z";
+
+fn write_manifest(stem: &str, version: &str, manifest: &str) {
+    let path = format!(
+        "{}/repo/publisher/openindiana.org/pkg/{}",
+        PATH,
+        encode(stem)
+    );
+    fs::create_dir_all(&path).unwrap();
+    fs::write(format!("{}/{}", path, encode(version)), manifest).unwrap();
+}
+
+/// Creates archive with library/a obsoleted, new library/new and package with long name
+fn create_archive() -> String {
+    let _ = fs::remove_dir_all(PATH);
+
+    write_manifest("library/a", V2, "set name=pkg.obsolete value=true\n");
+    write_manifest(
+        "library/new",
+        V1,
+        "depend fmri=pkg:/library/missing type=require\n\
+         file abcd path=usr/bin/new owner=root group=bin mode=0555\n",
+    );
+    write_manifest(&format!("library/{}", "x".repeat(120)), V1, "");
+
+    fs::create_dir_all(format!("{}/repo/publisher/openindiana.org/file/ab", PATH)).unwrap();
+    fs::write(
+        format!("{}/repo/publisher/openindiana.org/file/ab/abcd", PATH),
+        "payload",
+    )
+    .unwrap();
+
+    let archive = format!("{}/test.p5p", PATH);
+    let status = Command::new("tar")
+        .args(["--format=pax", "-cf", &archive, "-C"])
+        .arg(format!("{}/repo", PATH))
+        .arg("publisher")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    archive
+}
+
+#[test]
+fn p5p_archive() {
+    let archive = create_archive();
+
+    let mut components = Components::default();
+    load_archive(&mut components, archive.as_ref()).unwrap();
+
+    assert!(get!(components.get_package_by_fmri(&f("library/a")).unwrap()).is_obsolete());
+    assert!(components
+        .get_package_by_fmri(&f(&format!("library/{}", "x".repeat(120))))
+        .is_ok());
+    assert_eq!(
+        get!(components.get_package_by_fmri(&f("library/new")).unwrap())
+            .get_effective_version()
+            .unwrap()
+            .get_runtime_dependencies(),
+        &vec![DependTypes::Require(f("pkg:/library/missing"))]
+    );
+
+    assert!(load_archive(&mut Components::default(), "/tmp".as_ref()).is_err());
+
+    // archive over catalog
+    let mut components = Components::default();
+    components.add_package(
+        package("library/a")
+            .version(V1)
+            .publisher("openindiana.org")
+            .build(),
+    );
+    components.add_package(
+        package("library/b")
+            .version(V1)
+            .publisher("openindiana.org")
+            .depends(vec![DependTypes::Require(f("library/a"))])
+            .build(),
+    );
+    components.distribute_reverse_runtime_dependencies();
+    components
+        .new_component(
+            "c1".to_owned(),
+            vec![f("library/a"), f("library/b"), f("library/new")],
+        )
+        .unwrap();
+    components.new_component("c2".to_owned(), vec![]).unwrap();
+    components
+        .add_repo_dependencies(&"c2".to_owned(), vec![f("library/new")], &Build)
+        .unwrap();
+    components.check_problems().unwrap();
+
+    let diff = components
+        .overlay_archives(&[archive.clone().into()], &CheckOptions::default())
+        .unwrap();
+
+    // library/a has non-obsolete version in catalog
+    assert!(diff.added.iter().any(|p| matches!(
+        p,
+        PartlyObsoletedRequired(_, Runtime, b, _) if b == &f("library/b")
+    )));
+    assert!(diff.added.contains(&ObsoletedPackageInComponent(
+        f("library/a"),
+        "c1".to_owned()
+    )));
+    assert!(diff.added.contains(&NonExistingRequired(
+        DependTypes::Require(f("library/missing")),
+        Runtime,
+        f("library/new"),
+        "".to_owned()
+    )));
+    assert!(diff
+        .resolved
+        .contains(&NonExistingPackageInPkg5(f("library/new"), "c1".to_owned())));
+    assert!(diff.resolved.iter().any(|p| matches!(
+        p,
+        NonExistingRequired(_, Build, _, c) if c == "c2"
+    )));
+
+    let new = components.get_package_by_fmri(&f("library/new")).unwrap();
+    assert!(get!(new).is_in_component().is_some());
+    assert_eq!(get!(new).get_git_dependents(Build).unwrap().len(), 1);
+    assert_eq!(
+        get!(components.get_package_by_fmri(&f("library/a")).unwrap())
+            .get_runtime_dependents()
+            .len(),
+        1
+    );
+
+    fs::remove_dir_all(PATH).unwrap();
+}
+
+#[test]
+fn same_version_is_replaced() {
+    let mut components = Components::default();
+    components.add_package(
+        package("library/a")
+            .version(V1)
+            .publisher("openindiana.org")
+            .build(),
+    );
+    components.add_package(
+        package("library/a")
+            .version(V1)
+            .publisher("openindiana.org")
+            .obsolete(true)
+            .build(),
+    );
+
+    let a = components.get_package_by_fmri(&f("library/a")).unwrap();
+    assert_eq!(crate::get!(a).get_versions().len(), 1);
+    assert!(crate::get!(a).is_obsolete());
+}
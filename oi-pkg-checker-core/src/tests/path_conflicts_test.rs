@@ -0,0 +1,125 @@
+use crate::{
+    packages::{checks::CheckOptions, what_if::Plan},
+    problems::Problem::PathConflict,
+    tests::{f, package},
+    Components,
+};
+
+#[test]
+fn path_conflicts() {
+    let mut components = Components::default();
+    components.set_keep_actions(true);
+    components.add_package(
+        package("library/a")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .manifest(
+                "dir path=usr/bin owner=root group=bin mode=0755\n\
+         file aaaa path=usr/bin/foo owner=root group=bin mode=0555\n\
+         file cccc path=usr/bin/same owner=root group=bin mode=0555\n\
+         link path=usr/bin/python target=python3.9 mediator=python mediator-version=3.9\n\
+         link path=usr/bin/vi target=vim mediator=vi\n\
+         file gggg path=etc/foo.conf overlay=allow\n\
+         file dddd path=usr/lib/arch variant.arch=i386\n\
+         dir path=usr/share/doc owner=root group=bin mode=0755\n\
+         file eeee path=usr/share/old\n",
+            )
+            .build(),
+    );
+    components.add_package(
+        package("library/b")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .manifest(
+                "dir path=/usr/bin owner=root group=bin mode=0755\n\
+         file bbbb path=usr/bin/foo owner=root group=bin mode=0555\n\
+         file cccc path=usr/bin/same owner=root group=bin mode=0555\n\
+         link path=usr/bin/python target=python3.11 mediator=python mediator-version=3.11\n\
+         link path=usr/bin/vi target=vim\n\
+         file hhhh path=etc/foo.conf overlay=true\n\
+         file ffff path=usr/lib/arch variant.arch=sparc\n\
+         link path=usr/share/doc target=../doc\n",
+            )
+            .build(),
+    );
+    components.add_package(
+        package("library/obsolete")
+            .version("1.0")
+            .publisher("openindiana.org")
+            .manifest("file ffff path=usr/share/old\n")
+            .obsolete(true)
+            .build(),
+    );
+    components
+        .new_component("a".to_owned(), vec![f("library/a")])
+        .unwrap();
+
+    components.check_path_conflicts().unwrap();
+
+    assert_eq!(
+        components.problems.get_ref(),
+        &vec![
+            PathConflict(
+                "usr/bin/foo".to_owned(),
+                vec![
+                    (f("library/a"), "a".to_owned(), "file".to_owned()),
+                    (f("library/b"), "".to_owned(), "file".to_owned()),
+                ]
+            ),
+            PathConflict(
+                "usr/bin/same".to_owned(),
+                vec![
+                    (f("library/a"), "a".to_owned(), "file".to_owned()),
+                    (f("library/b"), "".to_owned(), "file".to_owned()),
+                ]
+            ),
+            PathConflict(
+                "usr/bin/vi".to_owned(),
+                vec![
+                    (f("library/a"), "a".to_owned(), "link".to_owned()),
+                    (f("library/b"), "".to_owned(), "link".to_owned()),
+                ]
+            ),
+            PathConflict(
+                "usr/share/doc".to_owned(),
+                vec![
+                    (f("library/a"), "a".to_owned(), "dir".to_owned()),
+                    (f("library/b"), "".to_owned(), "link".to_owned()),
+                ]
+            ),
+        ]
+    );
+    assert_eq!(
+        components
+            .problems
+            .get_problems_related_to_component(&"a".to_owned())
+            .len(),
+        4
+    );
+}
+
+#[test]
+fn what_if_path_conflict() {
+    let mut components = Components::default();
+    components.set_keep_actions(true);
+    components.add_package(
+        package("library/a")
+            .manifest("file aaaa path=usr/bin/foo\n")
+            .build(),
+    );
+    components.add_package(
+        package("library/b")
+            .manifest("file bbbb path=usr/bin/foo\n")
+            .build(),
+    );
+    components.check_all(&CheckOptions::default()).unwrap();
+
+    let plan: Plan = toml::from_str(r#"obsolete = ["library/b"]"#).unwrap();
+
+    let diff = components.what_if(&plan, &CheckOptions::default()).unwrap();
+    assert!(diff
+        .resolved
+        .iter()
+        .any(|p| matches!(p, PathConflict(path, _) if path == "usr/bin/foo")));
+    assert!(!diff.added.iter().any(|p| matches!(p, PathConflict(_, _))));
+}
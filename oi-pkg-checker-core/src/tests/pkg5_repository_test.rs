@@ -0,0 +1,114 @@
+use std::fs;
+
+use crate::{
+    assets::{
+        manifest::parse_action_line,
+        pkg5_repository::{encode, load_repository},
+    },
+    tests::f,
+    Components, DependTypes,
+};
+
+const PATH: &str = "/tmp/rust-oi-pkg-checker-core-pkg5-repository-test";
+
+fn write_manifest(publisher: &str, stem: &str, version: &str, manifest: &str) {
+    let path = format!("{}/publisher/{}/pkg/{}", PATH, publisher, encode(stem));
+    fs::create_dir_all(&path).unwrap();
+    fs::write(format!("{}/{}", path, encode(version)), manifest).unwrap();
+}
+
+#[test]
+fn parse_action() {
+    let action = parse_action_line(
+        r#"file 1234abcd path=usr/bin/foo "pkg.summary=x" mode=0555 value="a \"b\" c""#,
+    )
+    .unwrap();
+
+    assert_eq!(action.name, "file");
+    assert_eq!(action.payload, vec!["1234abcd".to_owned()]);
+    assert_eq!(action.get("path").unwrap(), "usr/bin/foo");
+    assert_eq!(action.get("value").unwrap(), "a \"b\" c");
+    assert!(parse_action_line("depend fmri=\"pkg:/a").is_err());
+}
+
+#[test]
+fn pkg5_repository() {
+    let _ = fs::remove_dir_all(PATH);
+    fs::create_dir_all(PATH).unwrap();
+    fs::write(
+        format!("{}/pkg5.repository", PATH),
+        "[publisher]\nprefix = openindiana.org\n\n[repository]\nversion = 4\n",
+    )
+    .unwrap();
+
+    let version = "1.0,5.11-2023.0.0.1:20230101T000000Z";
+    write_manifest(
+        "openindiana.org",
+        "library/foo",
+        version,
+        "set name=pkg.fmri value=pkg://openindiana.org/library/foo@1.0,5.11-2023.0.0.1:20230101T000000Z\n\
+         set name=pkg.summary value=\"foo library\"\n\
+         # comment\n\
+         depend fmri=pkg:/library/bar type=require pkg.debug.reason=\"bar for x=1\" \\\n    variant.arch=i386\n\
+         file 1234 path=usr/lib/libfoo.so.1 owner=root group=bin mode=0555\n",
+    );
+    write_manifest(
+        "openindiana.org",
+        "library/bar",
+        version,
+        "set name=pkg.fmri value=pkg://openindiana.org/library/bar@1.0,5.11-2023.0.0.1:20230101T000000Z\n",
+    );
+    write_manifest(
+        "openindiana.org",
+        "library/not-in-catalog",
+        version,
+        "set name=pkg.obsolete value=true\n",
+    );
+    fs::create_dir_all(format!("{}/publisher/openindiana.org/catalog", PATH)).unwrap();
+    fs::write(
+        format!("{}/publisher/openindiana.org/catalog/catalog.base.C", PATH),
+        format!(
+            r#"{{"openindiana.org": {{"library/foo": [{{"version": "{v}"}}], "library/bar": [{{"version": "{v}"}}]}}}}"#,
+            v = version
+        ),
+    )
+    .unwrap();
+
+    // publisher without catalog
+    write_manifest(
+        "hipster-encumbered",
+        "old",
+        version,
+        "set name=pkg.obsolete value=true\n",
+    );
+
+    let mut components = Components::default();
+    load_repository(&mut components, PATH.as_ref()).unwrap();
+
+    let foo = components.get_package_by_fmri(&f("library/foo")).unwrap();
+    assert_eq!(
+        get!(foo).get_fmri().get_publisher_as_ref_string().unwrap(),
+        "openindiana.org"
+    );
+    assert_eq!(
+        get!(foo)
+            .get_effective_version()
+            .unwrap()
+            .get_runtime_dependencies(),
+        &vec![DependTypes::Require(f("pkg:/library/bar"))]
+    );
+    assert_eq!(
+        get!(components.get_package_by_fmri(&f("library/bar")).unwrap())
+            .get_runtime_dependents()
+            .len(),
+        1
+    );
+    assert!(components
+        .get_package_by_fmri(&f("library/not-in-catalog"))
+        .is_err());
+    assert!(get!(components.get_package_by_fmri(&f("old")).unwrap()).is_obsolete());
+
+    assert!(load_repository(&mut Components::default(), "/tmp".as_ref()).is_err());
+
+    fs::remove_dir_all(PATH).unwrap();
+}
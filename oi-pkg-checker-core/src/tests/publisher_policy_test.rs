@@ -0,0 +1,91 @@
+use fmri::Publisher;
+
+use crate::{
+    packages::{
+        dependency_type::DependencyTypes::{Build, Runtime, Test},
+        publisher_policy::PublisherPolicy,
+    },
+    problems::Problem::PublisherPolicyViolation,
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+fn publisher(name: &str) -> Publisher {
+    Publisher::new(name.to_owned()).unwrap()
+}
+
+#[test]
+fn publisher_policy() {
+    let oi = "openindiana.org";
+    let encumbered = "hipster-encumbered";
+
+    let mut components = Components::default();
+    components.add_package(
+        package("a")
+            .publisher(oi)
+            .depends(vec![
+                DependTypes::Require(f("enc")),
+                DependTypes::Optional(f("enc")),
+                DependTypes::Require(f("b")),
+            ])
+            .build(),
+    );
+    components.add_package(package("b").publisher(oi).build());
+    components.add_package(
+        package("enc")
+            .publisher(encumbered)
+            .depends(vec![DependTypes::Require(f("b"))])
+            .build(),
+    );
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("c".to_owned(), vec![f("a")])
+        .unwrap();
+    components
+        .add_repo_dependencies(&"c".to_owned(), vec![f("enc")], &Build)
+        .unwrap();
+    components
+        .add_repo_dependencies(&"c".to_owned(), vec![f("enc")], &Test)
+        .unwrap();
+
+    components
+        .check_publisher_policy(&PublisherPolicy::default())
+        .unwrap();
+
+    let violations = components
+        .problems
+        .get_ref()
+        .iter()
+        .filter(|p| matches!(p, PublisherPolicyViolation(_, _, _, _, _, _)))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        violations,
+        vec![
+            PublisherPolicyViolation(
+                DependTypes::Require(f("enc")),
+                Runtime,
+                f("a"),
+                "".to_owned(),
+                publisher(oi),
+                publisher(encumbered)
+            ),
+            PublisherPolicyViolation(
+                DependTypes::Require(f("enc")),
+                Build,
+                f("none"),
+                "c".to_owned(),
+                publisher(oi),
+                publisher(encumbered)
+            ),
+        ]
+    );
+
+    assert!(PublisherPolicy::default().is_allowed(
+        &publisher(encumbered),
+        &publisher(oi),
+        "require"
+    ));
+}
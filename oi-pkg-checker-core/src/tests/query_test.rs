@@ -0,0 +1,108 @@
+use fmri::FMRI;
+
+use crate::{
+    packages::{
+        dependency_type::DependencyTypes::{Build, Test},
+        query::{Query, QueryDependency},
+    },
+    tests::{f, package},
+    Components,
+};
+
+fn names(fmris: Vec<FMRI>) -> Vec<String> {
+    fmris
+        .iter()
+        .map(|f| f.get_package_name_as_ref_string().clone())
+        .collect()
+}
+
+#[test]
+fn parse_query() {
+    assert_eq!(
+        Query::parse("name:library/python/* and not (obsolete or depends:require(renamed))")
+            .unwrap(),
+        Query::Name("library/python/*".to_owned())
+            & !(Query::Obsolete
+                | Query::Depends(QueryDependency::Require, Some(Box::new(Query::Renamed))))
+    );
+
+    assert!(Query::parse("name:").is_err());
+    assert!(Query::parse("obsolete and").is_err());
+    assert!(Query::parse("(obsolete").is_err());
+    assert!(Query::parse("depends:unknown").is_err());
+}
+
+#[test]
+fn query() {
+    let mut components = Components::default();
+    components.add_package(
+        package("library/python/a-311")
+            .requires(&["obsolete/x"])
+            .build(),
+    );
+    components.add_package(
+        package("library/python/b-311")
+            .requires(&["library/python/a-311"])
+            .build(),
+    );
+    components.add_package(package("obsolete/x").obsolete(true).build());
+    components.add_package(package("tool").requires(&["library/python/b-311"]).build());
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("python/a".to_owned(), vec![f("library/python/a-311")])
+        .unwrap();
+    components
+        .new_component("tool".to_owned(), vec![f("tool")])
+        .unwrap();
+    components
+        .add_repo_dependencies(&"tool".to_owned(), vec![f("library/python/b-311")], &Build)
+        .unwrap();
+    components
+        .add_repo_dependencies(&"python/a".to_owned(), vec![f("tool")], &Test)
+        .unwrap();
+    components.check_problems().unwrap();
+
+    let query = |q: &str| {
+        names(
+            components
+                .query_packages(&Query::parse(q).unwrap())
+                .unwrap(),
+        )
+    };
+
+    assert_eq!(
+        query("name:library/python/* and depends:require(obsolete)"),
+        vec!["library/python/a-311"]
+    );
+    assert_eq!(
+        query("not component:*"),
+        vec!["library/python/b-311", "obsolete/x"]
+    );
+    assert_eq!(query("dependent:build"), vec!["library/python/b-311"]);
+    assert_eq!(
+        query("dependent:require(dependent:build(name:tool))"),
+        vec!["library/python/a-311"]
+    );
+    assert_eq!(
+        query("dependent:test(contains(obsolete))"),
+        Vec::<String>::new()
+    );
+    assert_eq!(query("dependent:test"), vec!["tool"]);
+    assert_eq!(
+        query("problem:missing-component-for-package"),
+        vec!["library/python/b-311"]
+    );
+
+    let query_components = |q: &str| components.query_components(&Query::parse(q).unwrap());
+
+    assert_eq!(
+        query_components("not depends:test").unwrap(),
+        vec!["tool".to_owned()]
+    );
+    assert_eq!(
+        query_components("contains(depends:require(obsolete))").unwrap(),
+        vec!["python/a".to_owned()]
+    );
+    assert!(query_components("obsolete").is_err());
+}
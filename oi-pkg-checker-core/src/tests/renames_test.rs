@@ -0,0 +1,54 @@
+use crate::{
+    packages::renames::RenameChain,
+    problems::Problem::{RenameLoop, RenamedToNonExisting},
+    tests::{f, package},
+    Components,
+};
+
+fn new_data() -> Components {
+    let mut components = Components::default();
+    components.add_package(package("a").renamed_to("b@2").build());
+    components.add_package(package("b").renamed_to("c").build());
+    components.add_package(package("c").build());
+    components.add_package(package("d").renamed_to("e").build());
+    components.add_package(package("e").renamed_to("d").build());
+    components.add_package(package("x").renamed_to("y").build());
+    components.distribute_reverse_runtime_dependencies();
+    components
+}
+
+#[test]
+fn rename_chain() {
+    let components = new_data();
+
+    assert_eq!(
+        components.get_rename_chain(&f("a")).unwrap(),
+        RenameChain::Resolved(vec![f("a"), f("b"), f("c")])
+    );
+    assert_eq!(components.resolve_rename(&f("a")), Some(f("c")));
+    assert_eq!(
+        components.get_rename_chain(&f("e")).unwrap(),
+        RenameChain::Loop(vec![f("d"), f("e")])
+    );
+    assert_eq!(
+        components.get_rename_chain(&f("x")).unwrap(),
+        RenameChain::NonExisting(vec![f("x"), f("y")])
+    );
+    assert!(components.get_rename_chain(&f("z")).is_err());
+}
+
+#[test]
+fn rename_problems() {
+    let mut components = new_data();
+    components.check_problems().unwrap();
+
+    let problems = components.problems.get_ref();
+    assert!(problems.contains(&RenamedToNonExisting(f("x"), f("y"))));
+    assert_eq!(
+        problems
+            .iter()
+            .filter(|p| matches!(p, RenameLoop(_)))
+            .collect::<Vec<_>>(),
+        vec![&RenameLoop(vec![f("d"), f("e")])]
+    );
+}
@@ -0,0 +1,63 @@
+use crate::{
+    packages::dependency_type::DependencyTypes::Build,
+    problems::Problem::{MissingRequiredPackage, OwnPackageInRequiredPackages},
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+#[test]
+fn required_packages() {
+    let mut components = Components::default();
+    components.add_package(
+        package("a")
+            .depends(vec![
+                DependTypes::Require(f("b")),
+                DependTypes::Require(f("listed")),
+                DependTypes::Require(f("missing")),
+                DependTypes::Require(f("obsolete")),
+                DependTypes::Optional(f("optional")),
+            ])
+            .build(),
+    );
+    components.add_package(package("b").build());
+    components.add_package(package("listed").build());
+    components.add_package(package("missing").build());
+    components.add_package(package("obsolete").obsolete(true).build());
+    components.add_package(package("optional").build());
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("c".to_owned(), vec![f("a"), f("b")])
+        .unwrap();
+    components
+        .new_component(
+            "d".to_owned(),
+            vec![f("listed"), f("missing"), f("optional")],
+        )
+        .unwrap();
+    components
+        .add_repo_dependencies(&"c".to_owned(), vec![f("listed"), f("b")], &Build)
+        .unwrap();
+
+    components.check_problems().unwrap();
+
+    let problems = components
+        .problems
+        .get_problems_related_to_component(&"c".to_owned())
+        .into_iter()
+        .filter(|p| {
+            matches!(
+                p,
+                MissingRequiredPackage(_, _) | OwnPackageInRequiredPackages(_, _)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        problems,
+        vec![
+            OwnPackageInRequiredPackages(f("b"), "c".to_owned()),
+            MissingRequiredPackage(f("missing"), "c".to_owned()),
+        ]
+    );
+}
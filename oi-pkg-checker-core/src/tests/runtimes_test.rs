@@ -0,0 +1,62 @@
+use crate::{
+    packages::runtimes::{Language, Module},
+    tests::{f, package},
+    Components,
+};
+
+fn module(language: Language, name: &str) -> Module {
+    Module {
+        language,
+        name: name.to_owned(),
+    }
+}
+
+#[test]
+fn runtime_matrix() {
+    let mut components = Components::default();
+    components.add_package(package("runtime/python-39").build());
+    components.add_package(package("runtime/python-311").build());
+    components.add_package(package("runtime/python-27").obsolete(true).build());
+    components.add_package(package("runtime/perl-536").build());
+    components.add_package(
+        package("library/python/a-39")
+            .requires(&["runtime/python-39"])
+            .build(),
+    );
+    components.add_package(
+        package("library/python/a-311")
+            .requires(&["runtime/python-311", "library/python/b-39"])
+            .build(),
+    );
+    components.add_package(package("library/python/b-39").build());
+    components.add_package(package("library/python/b-311").obsolete(true).build());
+    components.add_package(package("library/python/old-27").build());
+    components.add_package(package("library/perl-5/c-536").build());
+    components.add_package(package("library/ruby/d-32").build());
+
+    let matrix = components.get_runtime_matrix();
+
+    assert_eq!(
+        matrix.supported[&Language::Python],
+        vec!["39".to_owned(), "311".to_owned()]
+    );
+    assert_eq!(matrix.obsolete[&Language::Python], vec!["27".to_owned()]);
+    assert_eq!(
+        matrix.missing,
+        vec![(
+            module(Language::Python, "library/python/b"),
+            vec!["311".to_owned()]
+        )]
+    );
+    assert_eq!(
+        matrix.only_obsolete,
+        vec![(
+            module(Language::Python, "library/python/old"),
+            vec!["27".to_owned()]
+        )]
+    );
+    assert_eq!(
+        matrix.mismatched,
+        vec![(f("library/python/a-311"), f("library/python/b-39"))]
+    );
+}
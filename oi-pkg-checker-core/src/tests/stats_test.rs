@@ -0,0 +1,73 @@
+use crate::{
+    packages::{
+        dependency_type::DependencyTypes::Build,
+        stats::{ComponentRank, PackageRank},
+    },
+    tests::{f, package},
+    Components,
+};
+
+fn rank(package: &str, transitive: usize, direct: usize) -> PackageRank {
+    PackageRank {
+        package: package.to_owned(),
+        direct,
+        transitive,
+    }
+}
+
+#[test]
+fn stats() {
+    let mut components = Components::default();
+    components.add_package(package("a").requires(&["b", "o"]).build());
+    components.add_package(package("b").requires(&["c"]).build());
+    components.add_package(package("c").requires(&["e"]).build());
+    components.add_package(package("d").requires(&["b"]).build());
+    components.add_package(package("e").requires(&["c"]).build());
+    components.add_package(package("o").obsolete(true).build());
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("c1".to_owned(), vec![f("d")])
+        .unwrap();
+    components
+        .add_repo_dependencies(&"c1".to_owned(), vec![f("a")], &Build)
+        .unwrap();
+    components.new_component("c2".to_owned(), vec![]).unwrap();
+
+    let stats = components.get_stats(2).unwrap();
+
+    assert_eq!(stats.packages, 5);
+    assert_eq!(stats.components, 2);
+    assert_eq!(
+        stats.most_depended_upon,
+        vec![rank("c", 4, 2), rank("e", 4, 1)]
+    );
+    assert_eq!(
+        stats.largest_build_closures,
+        vec![
+            ComponentRank {
+                component: "c1".to_owned(),
+                build_closure: 5
+            },
+            ComponentRank {
+                component: "c2".to_owned(),
+                build_closure: 0
+            }
+        ]
+    );
+    assert_eq!(
+        stats.fan_in,
+        vec![
+            ("0".to_owned(), 2),
+            ("1".to_owned(), 1),
+            ("2-3".to_owned(), 2)
+        ]
+    );
+    assert_eq!(stats.fan_out, vec![("1".to_owned(), 5)]);
+    assert_eq!(
+        stats.longest_chains,
+        vec![vec!["a", "b", "c"], vec!["d", "b", "c"]]
+    );
+    assert_eq!(stats.publishers.get("none"), Some(&6));
+    assert!(stats.to_json().unwrap().contains("\"most_depended_upon\""));
+}
@@ -0,0 +1,100 @@
+use crate::{
+    packages::{
+        checks::CheckOptions,
+        dependency_type::DependencyTypes::{Build, Runtime},
+        what_if::Plan,
+    },
+    problems::Problem::{NonExistingRequired, ObsoletedRequired, RenamedPackageInComponent},
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+fn new_data() -> Components {
+    let mut components = Components::default();
+    components.add_package(package("library/a").build());
+    components.add_package(
+        package("library/b")
+            .depends(vec![DependTypes::Require(f("library/a"))])
+            .build(),
+    );
+    components.add_package(package("library/c").build());
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component(
+            "c1".to_owned(),
+            vec![f("library/a"), f("library/b"), f("library/c")],
+        )
+        .unwrap();
+    components.new_component("c2".to_owned(), vec![]).unwrap();
+    components
+        .add_repo_dependencies(&"c2".to_owned(), vec![f("library/a")], &Build)
+        .unwrap();
+
+    components.check_problems().unwrap();
+    components
+}
+
+#[test]
+fn what_if() {
+    let components = new_data();
+
+    let plan: Plan = toml::from_str(
+        r#"
+        obsolete = ["library/a"]
+
+        [[rename]]
+        fmri = "library/c"
+        renamed_to = "library/b"
+        "#,
+    )
+    .unwrap();
+
+    let diff = components.what_if(&plan, &CheckOptions::default()).unwrap();
+    assert!(diff.resolved.is_empty());
+    assert!(diff.added.contains(&ObsoletedRequired(
+        DependTypes::Require(f("library/a")),
+        Runtime,
+        f("library/b"),
+        "".to_owned()
+    )));
+    assert!(diff.added.iter().any(|p| matches!(
+        p,
+        ObsoletedRequired(_, Build, _, c) if c == "c2"
+    )));
+    assert!(diff
+        .added
+        .contains(&RenamedPackageInComponent(f("library/c"), "c1".to_owned())));
+
+    // original data must stay untouched
+    assert!(!crate::get!(components.get_package_by_fmri(&f("library/a")).unwrap()).is_obsolete());
+}
+
+#[test]
+fn what_if_remove() {
+    let components = new_data();
+
+    let plan: Plan = toml::from_str(
+        r#"
+        remove = ["library/a"]
+
+        [[required_packages]]
+        component = "c2"
+        remove = ["library/a"]
+        add = ["library/c"]
+        "#,
+    )
+    .unwrap();
+
+    let diff = components.what_if(&plan, &CheckOptions::default()).unwrap();
+    assert!(diff.resolved.is_empty());
+    assert_eq!(
+        diff.added,
+        vec![NonExistingRequired(
+            DependTypes::Require(f("library/a")),
+            Runtime,
+            f("library/b"),
+            "".to_owned()
+        )]
+    );
+}
@@ -0,0 +1,110 @@
+use fmri::fmri_list::FMRIList;
+
+use crate::{
+    packages::{
+        dependency_type::DependencyTypes::Build,
+        why::{WhyEdge, WhyNode},
+    },
+    tests::{f, package},
+    Components, DependTypes,
+};
+
+fn new_data() -> Components {
+    let mut components = Components::default();
+    components.add_package(
+        package("a")
+            .depends(vec![
+                DependTypes::Require(f("b@1")),
+                DependTypes::RequireAny(FMRIList::from(vec![f("x"), f("c")])),
+            ])
+            .build(),
+    );
+    components.add_package(
+        package("b")
+            .depends(vec![DependTypes::Optional(f("c"))])
+            .build(),
+    );
+    components.add_package(package("c").build());
+    components.add_package(
+        package("d")
+            .depends(vec![DependTypes::Require(f("c"))])
+            .build(),
+    );
+    components.add_package(package("x").build());
+    components.distribute_reverse_runtime_dependencies();
+
+    components
+        .new_component("c1".to_owned(), vec![f("a")])
+        .unwrap();
+    components
+        .new_component("c2".to_owned(), vec![f("c")])
+        .unwrap();
+    components
+        .add_repo_dependencies(&"c1".to_owned(), vec![f("d")], &Build)
+        .unwrap();
+
+    components
+}
+
+#[test]
+fn why_node() {
+    let components = new_data();
+
+    assert_eq!(
+        components.get_why_node("a").unwrap(),
+        WhyNode::Package(f("a"))
+    );
+    assert_eq!(
+        components.get_why_node("c1").unwrap(),
+        WhyNode::Component("c1".to_owned())
+    );
+    assert!(components.get_why_node("pkg:/c1").is_err());
+    assert!(components.get_why_node("component:a").is_err());
+}
+
+#[test]
+fn why() {
+    let components = new_data();
+
+    let paths = components
+        .why(&WhyNode::Package(f("a")), &WhyNode::Package(f("c")), 3)
+        .unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(
+        paths[0].nodes,
+        vec![WhyNode::Package(f("a")), WhyNode::Package(f("c"))]
+    );
+    assert_eq!(
+        paths[1].nodes,
+        vec![
+            WhyNode::Package(f("a")),
+            WhyNode::Package(f("b")),
+            WhyNode::Package(f("c"))
+        ]
+    );
+    assert_eq!(
+        paths[1].edges,
+        vec![
+            WhyEdge::Runtime(Box::new(DependTypes::Require(f("b@1")))),
+            WhyEdge::Runtime(Box::new(DependTypes::Optional(f("c"))))
+        ]
+    );
+
+    let paths = components
+        .why(
+            &WhyNode::Component("c1".to_owned()),
+            &WhyNode::Component("c2".to_owned()),
+            5,
+        )
+        .unwrap();
+    assert_eq!(paths.len(), 3);
+    assert_eq!(paths[0].edges.len(), 2);
+    assert!(paths
+        .iter()
+        .any(|p| p.edges[0] == WhyEdge::Component(Build)));
+
+    assert!(components
+        .why(&WhyNode::Package(f("c")), &WhyNode::Package(f("a")), 1)
+        .unwrap()
+        .is_empty());
+}
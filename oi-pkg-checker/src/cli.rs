@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -20,17 +20,46 @@ pub enum Commands {
 
     /// Run analyze
     Run {
-        /// catalog path
+        /// catalog path (catalog.dependency.C or catalog directory with catalog.attrs)
         #[arg(long, value_name = "FILE")]
         catalog: Vec<PathBuf>,
 
+        /// path to local pkg5 file repository
+        #[arg(long, value_name = "DIR")]
+        repo: Vec<PathBuf>,
+
+        /// path to p5p archive, it is loaded over catalogs and repositories (if any)
+        #[arg(long, value_name = "FILE")]
+        archive: Vec<PathBuf>,
+
+        /// verify SHA-1 signatures of catalogs before loading them
+        #[arg(long, default_value = "false")]
+        verify_catalog: bool,
+
         /// set debug on
         #[arg(short, long, default_value = "false")]
         debug: bool,
 
         /// path to oi-userland/components
+        #[arg(long, value_name = "FILE", required_unless_present = "what_if")]
+        components: Option<PathBuf>,
+
+        /// keep all versions of packages (needed for history subcommand)
+        #[arg(long, default_value = "false")]
+        history: bool,
+
+        /// keep all actions from manifests of packages (from --repo, --archive and built components)
+        /// and check paths, links, mediators and licenses of packages
+        #[arg(long, default_value = "false")]
+        manifests: bool,
+
+        /// apply planned changes on data and print how problems change (data is not modified)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["catalog", "repo", "archive", "components", "history", "manifests", "verify_catalog"])]
+        what_if: Option<PathBuf>,
+
+        /// allowed dependencies between publishers (TOML), default policy is used if missing
         #[arg(long, value_name = "FILE")]
-        components: PathBuf,
+        publisher_policy: Option<PathBuf>,
     },
 
     /// Prints information about fmri and what packages need that fmri.
@@ -46,4 +75,120 @@ pub enum Commands {
         #[arg(short, long, default_value = "false")]
         human_readable: bool,
     },
+
+    /// Prints version timeline of fmri (data must be created with run --history)
+    History {
+        /// (valid) fmri
+        fmri: String,
+    },
+
+    /// Generates fixes of obsolete and renamed dependencies in component Makefiles and history files
+    #[command(group(ArgGroup::new("mode").required(true).args(["dry_run", "apply"])))]
+    Fix {
+        /// path to oi-userland/components
+        #[arg(long, value_name = "FILE")]
+        components: PathBuf,
+
+        /// print fixes as unified diff
+        #[arg(long)]
+        dry_run: bool,
+
+        /// write fixes into files
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Prints what depends (transitively) on fmris and problems that would appear if they were obsoleted
+    Impact {
+        /// (valid) fmris
+        #[arg(required = true)]
+        fmris: Vec<String>,
+
+        /// allowed dependencies between publishers (TOML), default policy is used if missing
+        #[arg(long, value_name = "FILE")]
+        publisher_policy: Option<PathBuf>,
+    },
+
+    /// Prints waves of components which can be built in parallel and cycles which have to be broken by hand
+    BuildOrder,
+
+    /// Prints the shortest dependency paths from one package or component to another
+    Why {
+        /// fmri or component name (use pkg:/ or component: prefix to be explicit)
+        from: String,
+
+        /// fmri or component name (use pkg:/ or component: prefix to be explicit)
+        to: String,
+
+        /// number of shortest paths to print
+        #[arg(short, default_value = "1")]
+        k: usize,
+    },
+
+    /// Prints packages pulled in by installing fmris
+    Closure {
+        /// (valid) fmris
+        #[arg(required = true)]
+        fmris: Vec<String>,
+
+        /// print all packages in closure
+        #[arg(short, long, default_value = "false")]
+        list: bool,
+    },
+
+    /// Prints packages which have to be installed to build component
+    BuildEnv {
+        /// component name
+        component: String,
+
+        /// include test dependencies
+        #[arg(long, default_value = "false")]
+        test: bool,
+
+        /// print all packages in build environment
+        #[arg(short, long, default_value = "false")]
+        list: bool,
+    },
+
+    /// Prints metrics of dependency graph
+    Stats {
+        /// number of items in rankings
+        #[arg(short, long, default_value = "20")]
+        top: usize,
+
+        /// print stats as JSON
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+
+    /// Prints packages (or components) matching query
+    Query {
+        /// query, e.g. 'name:library/python/* and depends:require(obsolete)'
+        query: String,
+
+        /// query components instead of packages
+        #[arg(short, long, default_value = "false")]
+        components: bool,
+
+        /// print result as JSON
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+
+    /// Prints packages which are not needed by anything (candidates for obsoletion)
+    Orphans,
+
+    /// Checks modules of python, perl and ruby against interpreter versions in catalog
+    Runtimes,
+
+    /// Applies incremental catalog updates on data and prints how problems change
+    UpdateCatalog {
+        /// catalog.update.<timestamp>.C files, they are applied in order of their names
+        #[arg(required = true, value_name = "FILE")]
+        updates: Vec<PathBuf>,
+
+        /// allowed dependencies between publishers (TOML), default policy is used if missing
+        #[arg(long, value_name = "FILE")]
+        publisher_policy: Option<PathBuf>,
+    },
 }
@@ -1,4 +1,7 @@
-use std::{path::Path, process::exit};
+use std::{
+    path::{Path, PathBuf},
+    process::exit,
+};
 
 use clap::Parser;
 use colored::Colorize;
@@ -7,12 +10,29 @@ use log::{debug, error, info, warn, LevelFilter};
 
 use oi_pkg_checker_core::problems::report_problem;
 use oi_pkg_checker_core::{
-    assets::{catalogs_c::load_catalog_c, open_indiana_oi_userland_git::load_git},
+    assets::{
+        catalog_signature::verify_catalog_signatures,
+        catalogs_c::{load_catalog, load_catalog_c},
+        open_indiana_oi_userland_git::{
+            load_component_licenses, load_component_manifests, load_git,
+        },
+        p5p_archive::load_archive,
+        pkg5_repository::load_repository,
+    },
     packages::{
+        checks::CheckOptions,
         components::Components,
+        cycles::format_cycle,
         dependency_type::DependencyTypes,
         dependency_type::DependencyTypes::{Build, SystemBuild, SystemTest, Test},
+        history::Transition,
+        impact::ImpactKind,
+        publisher_policy::PublisherPolicy,
+        query::Query,
+        renames::RenameChain,
         rev_depend_type::RevDependType::*,
+        what_if::Plan,
+        why::{WhyEdge, WhyNode},
     },
     report,
 };
@@ -34,21 +54,7 @@ fn main() {
     let args = Args::parse();
     if let Some(subcommand) = args.command {
         match subcommand {
-            Commands::PrintProblems => {
-                if !Path::new(&args.data).exists() {
-                    error!("{} doesn't exist", args.data.display());
-                    exit(1);
-                }
-
-                report(
-                    &Components::deserialize(&format!("{}", args.data.display()))
-                        .unwrap_or_else(|e| {
-                            error!("Failed to deserialize into components: {}", e);
-                            exit(1);
-                        })
-                        .problems,
-                )
-            }
+            Commands::PrintProblems => report(&load_components(&args.data).problems),
             Commands::CheckFMRI {
                 fmri,
                 hide_renamed,
@@ -61,16 +67,7 @@ fn main() {
 
                 info!("fmri: {}", fmri);
 
-                if !Path::new(&args.data).exists() {
-                    error!("{} doesn't exist", args.data.display());
-                    exit(1);
-                }
-
-                let components = Components::deserialize(&format!("{}", args.data.display()))
-                    .unwrap_or_else(|e| {
-                        error!("Failed to deserialize into components: {}", e);
-                        exit(1);
-                    });
+                let components = load_components(&args.data);
 
                 let package = components
                     .get_package_by_fmri(fmri)
@@ -80,12 +77,36 @@ fn main() {
                     })
                     .borrow();
 
+                if let Some(metadata) = package.get_metadata() {
+                    if let Some(summary) = &metadata.summary {
+                        info!("summary: {}", summary);
+                    }
+                    if let Some(description) = &metadata.description {
+                        info!("description: {}", description);
+                    }
+                    for classification in &metadata.classification {
+                        info!("classification: {}", classification);
+                    }
+                    if let Some(consolidation) = &metadata.consolidation {
+                        info!("consolidation: {}", consolidation);
+                    }
+                }
+
                 if package.is_obsolete() {
                     info!("package is obsolete");
                 }
 
                 if package.is_renamed() {
-                    info!("package is renamed");
+                    match package.get_renamed_to() {
+                        None => info!("package is renamed"),
+                        Some(renamed_to) => info!("package is renamed to {}", renamed_to),
+                    }
+
+                    if let Ok(RenameChain::Resolved(chain)) = components.get_rename_chain(fmri) {
+                        if chain.len() > 2 {
+                            info!("final package of rename chain: {}", chain.last().unwrap());
+                        }
+                    }
                 }
 
                 let runtime_deps = package.get_runtime_dependents();
@@ -202,24 +223,106 @@ fn main() {
             }
             Commands::Run {
                 catalog,
+                repo,
+                archive,
+                verify_catalog,
                 debug,
                 components: components_path,
+                history,
+                manifests,
+                what_if,
+                publisher_policy,
             } => {
                 debug_on(debug);
 
+                let options = check_options(publisher_policy);
+
+                if let Some(plan_path) = what_if {
+                    let plan = Plan::load(&plan_path).unwrap_or_else(|e| {
+                        error!("Failed to load plan: {}", e);
+                        exit(1);
+                    });
+
+                    let diff = load_components(&args.data)
+                        .what_if(&plan, &options)
+                        .unwrap_or_else(|e| {
+                            error!("Failed to apply plan: {}", e);
+                            exit(1);
+                        });
+
+                    if diff.added.is_empty() && diff.resolved.is_empty() {
+                        info!("plan does not change any problem");
+                        return;
+                    }
+
+                    if !diff.added.is_empty() {
+                        warn!("{}", "Problem/s introduced by plan:".bold());
+                        for problem in &diff.added {
+                            report_problem(problem);
+                        }
+                    }
+
+                    if !diff.resolved.is_empty() {
+                        info!("{}", "Problem/s resolved by plan:".bold());
+                        for problem in &diff.resolved {
+                            report_problem(problem);
+                        }
+                    }
+
+                    info!(
+                        "introduced problems: {}, resolved problems: {}",
+                        diff.added.len(),
+                        diff.resolved.len()
+                    );
+                    return;
+                }
+
                 let mut components = Components::default();
+                components.set_keep_history(history);
+                components.set_keep_actions(manifests);
 
-                if catalog.is_empty() {
+                // archives are loaded over catalogs and repositories, or instead of them
+                let overlay = !catalog.is_empty() || !repo.is_empty();
+
+                if !overlay && archive.is_empty() {
                     warn!("no catalog found")
                 }
 
                 for path in catalog {
-                    load_catalog_c(&mut components, &path).unwrap_or_else(|e| {
+                    if verify_catalog {
+                        verify_catalog_signatures(&path).unwrap_or_else(|e| {
+                            error!("Failed to verify catalog: {}", e);
+                            exit(1);
+                        });
+                    }
+
+                    match path.is_dir() {
+                        true => load_catalog(&mut components, &path),
+                        false => load_catalog_c(&mut components, &path),
+                    }
+                    .unwrap_or_else(|e| {
                         error!("Failed to load catalog: ({}): {}", path.display(), e);
                         exit(1);
                     });
                 }
 
+                for path in repo {
+                    load_repository(&mut components, &path).unwrap_or_else(|e| {
+                        error!("Failed to load repository: ({}): {}", path.display(), e);
+                        exit(1);
+                    });
+                }
+
+                if !overlay {
+                    for path in &archive {
+                        load_archive(&mut components, path).unwrap_or_else(|e| {
+                            error!("Failed to load archive: ({}): {}", path.display(), e);
+                            exit(1);
+                        });
+                    }
+                }
+
+                let components_path = components_path.unwrap();
                 match load_git(&mut components, &components_path) {
                     Ok(_) => {}
                     Err(e) => {
@@ -228,11 +331,560 @@ fn main() {
                     }
                 };
 
-                components.check_problems().unwrap_or_else(|e| {
-                    error!("Failed to check other problems: {}", e);
+                if manifests {
+                    load_component_manifests(&mut components, &components_path).unwrap_or_else(
+                        |e| {
+                            error!("Failed to load manifests of components: {}", e);
+                            exit(1);
+                        },
+                    );
+                    load_component_licenses(&mut components, &components_path).unwrap_or_else(
+                        |e| {
+                            error!("Failed to load licenses of components: {}", e);
+                            exit(1);
+                        },
+                    );
+                }
+
+                components.check_all(&options).unwrap_or_else(|e| {
+                    error!("Failed to check problems: {}", e);
+                    exit(1);
+                });
+
+                if overlay && !archive.is_empty() {
+                    let diff = components
+                        .overlay_archives(&archive, &options)
+                        .unwrap_or_else(|e| {
+                            error!("Failed to load archives over catalogs: {}", e);
+                            exit(1);
+                        });
+
+                    if !diff.added.is_empty() {
+                        warn!("{}", "Problem/s introduced by archive:".bold());
+                        for problem in &diff.added {
+                            report_problem(problem);
+                        }
+                    }
+
+                    info!(
+                        "introduced problems: {}, resolved problems: {}",
+                        diff.added.len(),
+                        diff.resolved.len()
+                    );
+                }
+
+                components.problems.sort();
+
+                components
+                    .serialize(&format!("{}", args.data.display()))
+                    .unwrap_or_else(|e| {
+                        error!("Failed to serialize into data: {}", e);
+                        exit(1);
+                    });
+            }
+            Commands::History { fmri } => {
+                let fmri = &FMRI::parse_raw(&fmri).unwrap_or_else(|e| {
+                    error!("Failed to parse fmri: {}", e);
+                    exit(1);
+                });
+
+                let components = load_components(&args.data);
+
+                if !components.is_history_kept() {
+                    warn!("data was created without --history, only effective version is known");
+                }
+
+                let package = components
+                    .get_package_by_fmri(fmri)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to get package with fmri ({}): {}", fmri, e);
+                        exit(1);
+                    })
+                    .borrow();
+
+                info!("fmri: {}", package.get_fmri());
+
+                for entry in package.get_history() {
+                    let version = entry.version.to_string();
+                    let version = version.split(':').next().unwrap().trim_start_matches('@');
+
+                    let label = format!(
+                        "{}{}",
+                        version,
+                        match &entry.timestamp {
+                            Some(t) => format!(" ({})", format_timestamp(t)),
+                            None => "".to_owned(),
+                        }
+                    )
+                    .bold();
+
+                    match entry.transition {
+                        Some(Transition::Obsoleted) => warn!("{} obsoleted", label),
+                        Some(Transition::Renamed) => warn!("{} renamed", label),
+                        Some(Transition::Revived) => info!("{} no longer obsolete/renamed", label),
+                        None => info!("{}", label),
+                    }
+
+                    for d in &entry.added {
+                        info!("    + {}", d);
+                    }
+                    for d in &entry.removed {
+                        info!("    - {}", d);
+                    }
+                }
+
+                if let Some(v) = package.get_effective_version() {
+                    info!("effective version: {}", v.get_version());
+                }
+            }
+            Commands::Fix {
+                components: components_path,
+                apply,
+                ..
+            } => {
+                let components = load_components(&args.data);
+
+                let patches = components
+                    .create_patches(&components.get_fixes(), &components_path)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to create patches: {}", e);
+                        exit(1);
+                    });
+
+                if patches.is_empty() {
+                    info!("nothing to fix");
+                }
+
+                for patch in patches {
+                    if apply {
+                        patch.apply().unwrap_or_else(|e| {
+                            error!("Failed to apply patch: {}", e);
+                            exit(1);
+                        });
+                        info!("fixed {}", patch.label);
+                    } else {
+                        print!("{}", patch.diff());
+                    }
+                }
+            }
+            Commands::Impact {
+                fmris,
+                publisher_policy,
+            } => {
+                let fmris = fmris
+                    .iter()
+                    .map(|f| {
+                        FMRI::parse_raw(f).unwrap_or_else(|e| {
+                            error!("Failed to parse fmri: {}", e);
+                            exit(1);
+                        })
+                    })
+                    .collect::<Vec<FMRI>>();
+
+                let components = load_components(&args.data);
+
+                let impact = components
+                    .get_impact(&fmris, &check_options(publisher_policy))
+                    .unwrap_or_else(|e| {
+                        error!("Failed to compute impact: {}", e);
+                        exit(1);
+                    });
+
+                let print_packages = |packages: &Vec<(FMRI, ImpactKind)>| {
+                    for (fmri, kind) in packages {
+                        info!(
+                            "    {} ({})",
+                            fmri,
+                            match kind {
+                                ImpactKind::Require => "require",
+                                ImpactKind::Soft => "optional/conditional/group",
+                                ImpactKind::Build => "build/test",
+                            }
+                        );
+                    }
+                };
+
+                for component in &impact.components {
+                    let types = component
+                        .dependency_types
+                        .iter()
+                        .map(|t| t.to_string())
+                        .collect::<Vec<String>>();
+
+                    if types.is_empty() {
+                        info!("{}", format!("component {}:", component.name).bold());
+                    } else {
+                        info!(
+                            "{}",
+                            format!("component {} ({}):", component.name, types.join(", ")).bold()
+                        );
+                    }
+                    print_packages(&component.packages);
+                }
+
+                if !impact.packages.is_empty() {
+                    info!("{}", "packages without component:".bold());
+                    print_packages(&impact.packages);
+                }
+
+                info!(
+                    "affected packages: {}, affected components: {}",
+                    impact
+                        .components
+                        .iter()
+                        .map(|c| c.packages.len())
+                        .sum::<usize>()
+                        + impact.packages.len(),
+                    impact.components.len()
+                );
+
+                if !impact.problems.is_empty() {
+                    warn!("{}", "Problem/s that would appear after obsoletion:".bold());
+                    for problem in &impact.problems {
+                        report_problem(problem);
+                    }
+                }
+            }
+            Commands::BuildOrder => {
+                let build_order = load_components(&args.data).get_build_order();
+
+                let in_cycle = |name: &String| {
+                    build_order
+                        .cycles
+                        .iter()
+                        .any(|c| c.components.contains(name))
+                };
+
+                for (i, wave) in build_order.waves.iter().enumerate() {
+                    info!("{}", format!("wave {}:", i + 1).bold());
+                    for name in wave {
+                        info!(
+                            "    {}{}",
+                            name,
+                            if in_cycle(name) { " (cycle)" } else { "" }
+                        );
+                    }
+                }
+
+                if !build_order.cycles.is_empty() {
+                    warn!("{}", "cycles which have to be broken by hand:".bold());
+                    for cycle in &build_order.cycles {
+                        warn!("{}", cycle.components.join(", "));
+                        for path in &cycle.paths {
+                            info!("    {}", format_cycle(path));
+                        }
+                    }
+                }
+            }
+            Commands::Why { from, to, k } => {
+                let components = load_components(&args.data);
+
+                let node = |name: &String| {
+                    components.get_why_node(name).unwrap_or_else(|e| {
+                        error!("Failed to find {}: {}", name, e);
+                        exit(1);
+                    })
+                };
+                let (from, to) = (node(&from), node(&to));
+
+                let paths = components.why(&from, &to, k).unwrap_or_else(|e| {
+                    error!("Failed to find paths: {}", e);
                     exit(1);
                 });
 
+                if paths.is_empty() {
+                    info!("no path found");
+                }
+
+                let format_node = |node: &WhyNode| match node {
+                    WhyNode::Package(fmri) => match components.get_owning_component(fmri) {
+                        Some(component) => format!("{} (component {})", fmri, component),
+                        None => format!("{} (no component)", fmri),
+                    },
+                    WhyNode::Component(name) => format!("component {}", name),
+                };
+
+                for (i, path) in paths.iter().enumerate() {
+                    info!(
+                        "{}",
+                        format!("path {} ({} hops):", i + 1, path.edges.len()).bold()
+                    );
+                    info!("    {}", format_node(&path.nodes[0]));
+                    for (edge, node) in path.edges.iter().zip(path.nodes.iter().skip(1)) {
+                        let edge = match edge {
+                            WhyEdge::Runtime(d) => (**d).clone().get_name_and_content_as_string().0,
+                            WhyEdge::Contains => "contains".to_owned(),
+                            WhyEdge::Component(d) => d.to_string(),
+                        };
+                        info!("      --{}--> {}", edge, format_node(node));
+                    }
+                }
+            }
+            Commands::Closure { fmris, list } => {
+                let fmris = fmris
+                    .iter()
+                    .map(|f| {
+                        FMRI::parse_raw(f).unwrap_or_else(|e| {
+                            error!("Failed to parse fmri: {}", e);
+                            exit(1);
+                        })
+                    })
+                    .collect::<Vec<FMRI>>();
+
+                let closure = load_components(&args.data)
+                    .get_install_closure(&fmris)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to compute closure: {}", e);
+                        exit(1);
+                    });
+
+                if list {
+                    info!("{}", "packages in closure:".bold());
+                    for fmri in &closure.packages {
+                        info!("    {}", fmri);
+                    }
+                }
+
+                info!("closure size: {}", closure.packages.len());
+
+                for fmri in &closure.obsolete {
+                    warn!("obsolete package in closure: {}", fmri);
+                }
+
+                for fmri in &closure.renamed {
+                    warn!("renamed package in closure: {}", fmri);
+                }
+
+                for (fmri, d) in &closure.unresolvable {
+                    let (name, content) = d.clone().get_name_and_content_as_string();
+                    error!(
+                        "package {} has unresolvable dependency {} ({})",
+                        fmri, content, name
+                    );
+                }
+            }
+            Commands::BuildEnv {
+                component,
+                test,
+                list,
+            } => {
+                let build_env = load_components(&args.data)
+                    .get_build_env(&component, test)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to compute build environment: {}", e);
+                        exit(1);
+                    });
+
+                if list {
+                    info!("{}", "packages in build environment:".bold());
+                    for fmri in &build_env.packages {
+                        info!("    {}", fmri);
+                    }
+                }
+
+                info!("build environment size: {}", build_env.packages.len());
+
+                for fmri in &build_env.own {
+                    warn!("package of component itself (needs bootstrap): {}", fmri);
+                }
+
+                for fmri in &build_env.obsolete {
+                    warn!("obsolete package in build environment: {}", fmri);
+                }
+
+                if let Some(publisher) = &build_env.publisher {
+                    for fmri in &build_env.other_publisher {
+                        warn!("package from other publisher than {}: {}", publisher, fmri);
+                    }
+                }
+
+                for (fmri, d) in &build_env.unresolvable {
+                    let (name, content) = d.clone().get_name_and_content_as_string();
+                    error!(
+                        "package {} has unresolvable dependency {} ({})",
+                        fmri, content, name
+                    );
+                }
+            }
+            Commands::Stats { top, json } => {
+                let stats = load_components(&args.data)
+                    .get_stats(top)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to compute stats: {}", e);
+                        exit(1);
+                    });
+
+                if json {
+                    println!(
+                        "{}",
+                        stats.to_json().unwrap_or_else(|e| {
+                            error!("{}", e);
+                            exit(1);
+                        })
+                    );
+                    return;
+                }
+
+                info!(
+                    "packages (non-obsolete): {}, components: {}",
+                    stats.packages, stats.components
+                );
+
+                info!("{}", "most depended-upon packages:".bold());
+                info!("    {:>10}  {:>6}  package", "transitive", "direct");
+                for rank in &stats.most_depended_upon {
+                    info!(
+                        "    {:>10}  {:>6}  {}",
+                        rank.transitive, rank.direct, rank.package
+                    );
+                }
+
+                info!("{}", "components with the largest build closures:".bold());
+                info!("    {:>8}  component", "packages");
+                for rank in &stats.largest_build_closures {
+                    info!("    {:>8}  {}", rank.build_closure, rank.component);
+                }
+
+                for (label, histogram) in [
+                    ("fan-in (direct dependents):", &stats.fan_in),
+                    ("fan-out (direct dependencies):", &stats.fan_out),
+                ] {
+                    info!("{}", label.bold());
+                    info!("    {:>9}  packages", "count");
+                    for (bucket, count) in histogram {
+                        info!("    {:>9}  {}", bucket, count);
+                    }
+                }
+
+                info!("{}", "the longest dependency chains:".bold());
+                for chain in &stats.longest_chains {
+                    info!("    {:>3}  {}", chain.len(), chain.join(" -> "));
+                }
+
+                info!("{}", "packages per publisher:".bold());
+                for (publisher, count) in &stats.publishers {
+                    info!("    {:>8}  {}", count, publisher);
+                }
+            }
+            Commands::Query {
+                query,
+                components: query_components,
+                json,
+            } => {
+                let query = Query::parse(&query).unwrap_or_else(|e| {
+                    error!("Failed to parse query: {}", e);
+                    exit(1);
+                });
+
+                let components = load_components(&args.data);
+                let result = match query_components {
+                    true => components.query_components(&query),
+                    false => components
+                        .query_packages(&query)
+                        .map(|fmris| fmris.iter().map(|f| f.to_string()).collect()),
+                }
+                .unwrap_or_else(|e| {
+                    error!("Failed to evaluate query: {}", e);
+                    exit(1);
+                });
+
+                if json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&result).unwrap_or_else(|e| {
+                            error!("Failed to serialize result: {}", e);
+                            exit(1);
+                        })
+                    );
+                    return;
+                }
+
+                for item in result {
+                    info!("{}", item);
+                }
+            }
+            Commands::Orphans => {
+                let orphans = load_components(&args.data)
+                    .get_orphans()
+                    .unwrap_or_else(|e| {
+                        error!("Failed to find orphans: {}", e);
+                        exit(1);
+                    });
+
+                for (header, list) in [
+                    ("leaf applications:", &orphans.applications),
+                    ("orphaned libraries:", &orphans.libraries),
+                ] {
+                    info!("{}", header.bold());
+                    for (fmri, component) in list {
+                        match component {
+                            Some(component) => info!("    {} (component: {})", fmri, component),
+                            None => info!("    {} (not in any component)", fmri),
+                        }
+                    }
+                }
+            }
+            Commands::Runtimes => {
+                let matrix = load_components(&args.data).get_runtime_matrix();
+
+                for (language, versions) in &matrix.supported {
+                    info!("supported {} versions: {}", language, versions.join(", "));
+                }
+                for (language, versions) in &matrix.obsolete {
+                    info!("obsolete {} versions: {}", language, versions.join(", "));
+                }
+
+                for (module, versions) in &matrix.missing {
+                    warn!(
+                        "{} module {} is missing for versions: {}",
+                        module.language,
+                        module.name,
+                        versions.join(", ")
+                    );
+                }
+
+                for (module, versions) in &matrix.only_obsolete {
+                    warn!(
+                        "{} module {} is published only for unsupported versions: {}",
+                        module.language,
+                        module.name,
+                        versions.join(", ")
+                    );
+                }
+
+                for (fmri, dependency) in &matrix.mismatched {
+                    error!(
+                        "package {} depends on other interpreter version: {}",
+                        fmri, dependency
+                    );
+                }
+            }
+            Commands::UpdateCatalog {
+                updates,
+                publisher_policy,
+            } => {
+                let mut components = load_components(&args.data);
+
+                let diff = components
+                    .apply_catalog_updates(&updates, &check_options(publisher_policy))
+                    .unwrap_or_else(|e| {
+                        error!("Failed to apply catalog updates: {}", e);
+                        exit(1);
+                    });
+
+                if !diff.added.is_empty() {
+                    warn!("{}", "Problem/s introduced by updates:".bold());
+                    for problem in &diff.added {
+                        report_problem(problem);
+                    }
+                }
+
+                info!(
+                    "introduced problems: {}, resolved problems: {}",
+                    diff.added.len(),
+                    diff.resolved.len()
+                );
+
                 components.problems.sort();
 
                 components
@@ -246,6 +898,47 @@ fn main() {
     }
 }
 
+fn check_options(publisher_policy: Option<PathBuf>) -> CheckOptions {
+    CheckOptions {
+        publisher_policy: match publisher_policy {
+            Some(path) => PublisherPolicy::load(&path).unwrap_or_else(|e| {
+                error!("Failed to load publisher policy: {}", e);
+                exit(1);
+            }),
+            None => PublisherPolicy::default(),
+        },
+    }
+}
+
+fn load_components(data: &Path) -> Components {
+    if !data.exists() {
+        error!("{} doesn't exist", data.display());
+        exit(1);
+    }
+
+    Components::deserialize(&format!("{}", data.display())).unwrap_or_else(|e| {
+        error!("Failed to deserialize into components: {}", e);
+        exit(1);
+    })
+}
+
+/// formats "20171212T185746Z" as "2017-12-12 18:57:46"
+fn format_timestamp(timestamp: &str) -> String {
+    if timestamp.len() != 16 || !timestamp.is_ascii() {
+        return timestamp.to_owned();
+    }
+
+    format!(
+        "{}-{}-{} {}:{}:{}",
+        &timestamp[0..4],
+        &timestamp[4..6],
+        &timestamp[6..8],
+        &timestamp[9..11],
+        &timestamp[11..13],
+        &timestamp[13..15]
+    )
+}
+
 fn debug_on(debug: bool) {
     if debug {
         log::set_max_level(LevelFilter::Debug);